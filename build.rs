@@ -1,19 +1,37 @@
-fn load_env_file(env_file: &str) {
-    env_file.lines().for_each(|line| {
-        let (key, value) = {
-            let mut it = line.split('=');
+use std::{env, fs, path::Path};
 
-            (it.next().unwrap(), it.next().unwrap())
-        };
+#[path = "src/env_files.rs"]
+mod env_files;
 
-        println!("cargo:rustc-env={key}={value}");
-    });
-}
+use env_files::{generate_config, merge_env_files, ENV_FILES};
+
+// Included by `src/config.rs`
+const GENERATED_FILE: &str = "config.rs";
 
 fn main() {
-    let env_file = include_str!(".env");
+    // NOTE: A missing file counts as changed, so this reruns on every build until it exists
+    ENV_FILES.iter().for_each(|path| {
+        println!("cargo:rerun-if-changed={path}");
+    });
 
-    [env_file].into_iter().for_each(|file| {
-        load_env_file(file);
+    let env_files = ENV_FILES
+        .iter()
+        .filter_map(|path| Some((*path, fs::read_to_string(path).ok()?)))
+        .collect::<Vec<_>>();
+    let env_files = env_files
+        .iter()
+        .map(|(path, env_file)| (*path, env_file.as_str()))
+        .collect::<Vec<_>>();
+
+    // NOTE: A panic is how a build script fails, cargo shows the message
+    let entries = merge_env_files(&env_files).unwrap_or_else(|error| panic!("{error}"));
+    let config = generate_config(&entries).unwrap_or_else(|error| panic!("{error}"));
+
+    entries.iter().for_each(|(key, value)| {
+        println!("cargo:rustc-env={key}={value}");
     });
+
+    let out_dir = env::var("OUT_DIR").expect("cargo sets OUT_DIR for build scripts");
+    fs::write(Path::new(&out_dir).join(GENERATED_FILE), config)
+        .unwrap_or_else(|error| panic!("failed to write {GENERATED_FILE}: {error}"));
 }
@@ -0,0 +1,88 @@
+/*
+    ECS walkthrough
+
+    Builds a `Game` without opening a window or touching the physics, adds a few
+    entities with different components and prints what the queries see.
+
+        cargo run --example ecs
+        cargo run --example ecs --no-default-features
+*/
+
+use macroquad::prelude::*;
+
+use rust_2d_macro::{components, Game, ScreenSpaceComponent, TextureComponent};
+
+fn main() {
+    // NOTE: Seeded so that every run prints the same anchors
+    let mut game = Game::with_seed(7);
+
+    // NOTE: Nothing is drawn here, so no texture has to be uploaded
+    let texture = Texture2D::empty();
+
+    // Spawn: a sprite only entity and a couple of HUD icons on top of it
+    let sprite = game.new_entity("Sprite");
+    game.add_texture(
+        sprite,
+        TextureComponent {
+            texture,
+            size: vec2(16.0, 16.0),
+            color: WHITE,
+            render_scale: Vec2::ONE,
+            rotation_offset: 0.0,
+        },
+    );
+
+    let icons = (0..3)
+        .map(|_| {
+            let icon = game.new_entity("Icon");
+            let anchor = game.random_vec2_in(Rect::new(0.0, 0.0, 800.0, 600.0));
+
+            game.add_texture(
+                icon,
+                TextureComponent {
+                    texture,
+                    size: vec2(8.0, 8.0),
+                    color: YELLOW,
+                    render_scale: Vec2::ONE,
+                    rotation_offset: 0.0,
+                },
+            );
+            game.add_screen_space(icon, ScreenSpaceComponent { anchor });
+
+            icon
+        })
+        .collect::<Vec<_>>();
+
+    // Flags: disabling keeps the components but hides the entity from queries
+    game.set_enabled(icons[0], false);
+
+    println!("Entities:");
+    for (entity, label, bitset, _position) in game.dump() {
+        println!(
+            "  {entity:?} {label} {}",
+            components::flags_to_names(bitset).join("|")
+        );
+    }
+
+    // Query: every enabled entity with both a texture and a screen space anchor
+    println!("Icons on screen:");
+    for (entity, texture, screen_space) in game.query2::<TextureComponent, ScreenSpaceComponent>() {
+        println!("  {entity:?} at {:?}", screen_space.rect(texture.size));
+    }
+
+    // Despawn: the handle stays dead even once its slot is reused
+    game.remove_entity(icons[1]);
+    let reused = game.new_entity("Reused");
+
+    println!(
+        "{:?} alive: {}, {reused:?} alive: {}",
+        icons[1],
+        game.is_alive(icons[1]),
+        game.is_alive(reused),
+    );
+
+    match game.validate() {
+        Ok(()) => println!("{} entities, all consistent", game.dump().len()),
+        Err(violations) => println!("violations:\n{}", violations.join("\n")),
+    }
+}
@@ -2,10 +2,22 @@
     BitSets
 */
 
-type BitSetImpl = i8;
+use std::fmt;
+
+use crate::components::{self, NUM_COMPONENTS};
+
+type BitSetImpl = u64;
 pub type Flag = BitSetImpl;
 
-#[derive(Clone, Copy)]
+#[inline]
+const fn debug_assert_in_range(flag: Flag) {
+    debug_assert!(
+        NUM_COMPONENTS >= BitSetImpl::BITS as usize || flag >> NUM_COMPONENTS == 0,
+        "flag has bits set past NUM_COMPONENTS"
+    );
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
 pub struct BitSet {
     bits: BitSetImpl,
 }
@@ -25,13 +37,39 @@ impl BitSet {
         Self { bits: 0 }
     }
 
+    /// The flag for the component with the given bit `index`.
+    #[inline]
+    pub const fn flag(index: u32) -> Flag {
+        debug_assert!(
+            (index as usize) < NUM_COMPONENTS,
+            "flag index out of range, is NUM_COMPONENTS up to date?"
+        );
+
+        1 << index
+    }
+
+    #[inline]
+    pub const fn bits(&self) -> BitSetImpl {
+        self.bits
+    }
+
+    /// Every set bit as its own flag, lowest first.
+    pub fn iter_flags(&self) -> impl Iterator<Item = Flag> {
+        let bits = self.bits;
+        (0..BitSetImpl::BITS)
+            .map(|index| 1 << index)
+            .filter(move |flag| bits & flag != 0)
+    }
+
     #[inline]
     pub fn include_flag(&mut self, flag: Flag) {
+        debug_assert_in_range(flag);
         self.bits |= flag
     }
 
     #[inline]
     pub fn exclude_flag(&mut self, flag: Flag) {
+        debug_assert_in_range(flag);
         self.bits &= !flag
     }
 
@@ -45,3 +83,54 @@ impl BitSet {
         (other.bits & self.bits) == self.bits
     }
 }
+
+impl fmt::Debug for BitSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "BitSet(")?;
+
+        for (i, flag) in self.iter_flags().enumerate() {
+            if i > 0 {
+                write!(f, " | ")?;
+            }
+
+            match components::name(flag) {
+                Some(name) => write!(f, "{name}")?,
+                None => write!(f, "{flag:#x}")?,
+            }
+        }
+
+        write!(f, ")")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn flags_in_range_set_their_own_bit() {
+        assert_eq!(BitSet::flag(0), 1);
+        assert_eq!(BitSet::flag(3), 0b1000);
+
+        let last = BitSet::flag(NUM_COMPONENTS as u32 - 1);
+        let mut bitset = BitSet::empty();
+        bitset.include_flag(last);
+        assert!(bitset.contains(last));
+        bitset.exclude_flag(last);
+        assert_eq!(bitset, BitSet::empty());
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "flag index out of range")]
+    fn flag_indices_past_the_components_assert() {
+        BitSet::flag(NUM_COMPONENTS as u32);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "flag has bits set past NUM_COMPONENTS")]
+    fn including_flags_past_the_components_asserts() {
+        BitSet::empty().include_flag(1 << NUM_COMPONENTS);
+    }
+}
@@ -0,0 +1,441 @@
+/*
+    Screen capture
+
+    NOTE: Grabbing a frame reads the framebuffer back from the GPU, so it has to run
+          after everything, including the GUI, has been drawn for the frame. Encoding
+          the PNG is the slow part and happens on its own thread, which also means this
+          doesn't work on wasm.
+*/
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use macroquad::prelude::*;
+
+pub const OUTPUT_DIRECTORY: &str = "assets/output";
+
+/// A path in `directory` like `screenshot_1700000000123.png`, unique per millisecond.
+pub fn timestamped_path(directory: impl AsRef<Path>, prefix: &str, extension: &str) -> PathBuf {
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default();
+
+    directory
+        .as_ref()
+        .join(format!("{prefix}_{millis}.{extension}"))
+}
+
+/// Grabs the current frame and writes it to a new PNG in `directory` in the background.
+pub fn save_screenshot(directory: impl AsRef<Path>) -> io::Result<PathBuf> {
+    fs::create_dir_all(&directory)?;
+
+    let path = timestamped_path(directory, "screenshot", "png");
+
+    let mut target = None;
+    let image = grab_screen_data(&mut target);
+    if let Some(target) = target {
+        target.delete();
+    }
+
+    let thread_path = path.clone();
+    thread::spawn(move || {
+        // NOTE: `export_png` panics when it fails, which only takes this thread down
+        image.export_png(&thread_path.to_string_lossy());
+        println!("Saved screenshot to {}", thread_path.display());
+    });
+
+    Ok(path)
+}
+
+/// Records every `interval`th frame while running and saves the clip as a tiled
+/// PNG sprite sheet, read left to right and top to bottom.
+#[derive(Debug)]
+pub struct Recorder {
+    // Frames to skip between two captured ones, `1` captures every frame
+    pub interval: u32,
+    // Seconds of rendered time one recording covers at most before it stops by itself
+    pub duration: f32,
+    // Captured frames a recording buffers at most, however long `duration` is
+    pub max_frames: usize,
+    // Every tile is the screen shrunk by this factor
+    pub downsample: u32,
+
+    pub directory: PathBuf,
+
+    recording: bool,
+    frame_counter: u32,
+    elapsed: f32,
+
+    // NOTE: Stored top to bottom, unlike the bottom up rows `get_texture_data` returns
+    frames: Vec<Image>,
+
+    // Reused for every captured frame, see `grab_screen_data`
+    target: Option<Texture2D>,
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self {
+            interval: 4,
+            // NOTE: 60 frames, 4 frames apart, at 60 fps
+            duration: 4.0,
+            max_frames: 60,
+            downsample: 4,
+
+            directory: PathBuf::from(OUTPUT_DIRECTORY),
+
+            recording: false,
+            frame_counter: 0,
+            elapsed: 0.0,
+
+            frames: vec![],
+
+            target: None,
+        }
+    }
+}
+
+impl Recorder {
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// Whether the recording has covered `duration` seconds or buffered `max_frames`.
+    pub fn is_full(&self) -> bool {
+        self.elapsed >= self.duration || self.frames.len() >= self.max_frames
+    }
+
+    pub fn start(&mut self) {
+        self.recording = true;
+        self.frame_counter = 0;
+        self.elapsed = 0.0;
+        self.frames.clear();
+    }
+
+    /// Stops recording and writes the sprite sheet in the background. Returns the path
+    /// it is written to, or `None` when no frames were captured.
+    pub fn stop(&mut self) -> io::Result<Option<PathBuf>> {
+        self.recording = false;
+
+        let frames = std::mem::take(&mut self.frames);
+        if frames.is_empty() {
+            return Ok(None);
+        }
+
+        fs::create_dir_all(&self.directory)?;
+
+        let path = timestamped_path(&self.directory, "recording", "png");
+
+        let thread_path = path.clone();
+        thread::spawn(move || {
+            let frame_count = frames.len();
+
+            sprite_sheet(&frames).export_png(&thread_path.to_string_lossy());
+            println!("Saved {frame_count} frames to {}", thread_path.display());
+        });
+
+        Ok(Some(path))
+    }
+
+    /*
+        Once per frame, after everything is drawn like for screenshots. `toggled` starts
+        a recording or stops and saves the running one, which otherwise grabs the frame
+        when it is due and stops by itself once full. `delta` is the time since the
+        previous frame. Returns where a stopped recording is saved to.
+    */
+    pub fn update(&mut self, toggled: bool, delta: f32) -> io::Result<Option<PathBuf>> {
+        self.update_with(toggled, delta, grab_screen_data)
+    }
+
+    // NOTE: `grab` stands in for the GPU read back in the tests
+    fn update_with(
+        &mut self,
+        toggled: bool,
+        delta: f32,
+        grab: impl FnOnce(&mut Option<Texture2D>) -> Image,
+    ) -> io::Result<Option<PathBuf>> {
+        if toggled {
+            if self.recording {
+                return self.stop();
+            }
+
+            self.start();
+        }
+
+        if self.recording {
+            self.capture(delta, grab);
+
+            if self.is_full() {
+                return self.stop();
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn capture(&mut self, delta: f32, grab: impl FnOnce(&mut Option<Texture2D>) -> Image) {
+        self.elapsed += delta;
+
+        let due = self.frame_counter.is_multiple_of(self.interval.max(1));
+        self.frame_counter = self.frame_counter.wrapping_add(1);
+
+        if !due {
+            return;
+        }
+
+        let frame = downsample(&grab(&mut self.target), self.downsample);
+
+        // NOTE: The tiles of a sheet have to match, so frames after a resize are dropped
+        let matches = self
+            .frames
+            .first()
+            .is_none_or(|first| first.width == frame.width && first.height == frame.height);
+
+        if matches {
+            self.frames.push(frame);
+        }
+    }
+}
+
+/*
+    The current frame with bottom up rows, like `get_screen_data` returns it, copied
+    through `target`. A missing `target` or one of the wrong size is replaced by a new
+    render texture of the screen's size, which the caller owns and has to delete.
+
+    NOTE: `get_screen_data` allocates a new render texture on every call which is never
+          freed, this is the same without the leak.
+*/
+fn grab_screen_data(target: &mut Option<Texture2D>) -> Image {
+    /*
+        SAFETY: Every use of the internal context below is short lived and nothing
+                else touches the context in between.
+    */
+
+    // NOTE: Same as `get_screen_data`, draw whatever is still batched first
+    unsafe { get_internal_gl() }.flush();
+
+    // NOTE: In physical pixels, unlike `screen_width` and `screen_height`
+    let (width, height) = unsafe { get_internal_gl() }.quad_context.screen_size();
+    let (width, height) = (width as u16, height as u16);
+
+    let texture = match *target {
+        Some(texture) if texture.width() as u16 == width && texture.height() as u16 == height => {
+            texture
+        }
+
+        _ => {
+            if let Some(texture) = target.take() {
+                texture.delete();
+            }
+
+            let texture =
+                Texture2D::from_miniquad_texture(macroquad::miniquad::Texture::new_render_texture(
+                    unsafe { get_internal_gl() }.quad_context,
+                    macroquad::miniquad::TextureParams {
+                        width: width as _,
+                        height: height as _,
+                        ..Default::default()
+                    },
+                ));
+
+            *target = Some(texture);
+            texture
+        }
+    };
+
+    texture.grab_screen();
+    texture.get_texture_data()
+}
+
+/// Shrinks `image` by `factor` by picking the nearest pixel, flipping the rows top to bottom.
+fn downsample(image: &Image, factor: u32) -> Image {
+    let factor = factor.max(1) as usize;
+    let (source_width, source_height) = (image.width as usize, image.height as usize);
+    let (width, height) = (
+        (source_width / factor).max(1),
+        (source_height / factor).max(1),
+    );
+
+    let mut bytes = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        let source_y = source_height - 1 - (y * factor).min(source_height - 1);
+
+        for x in 0..width {
+            let source_x = (x * factor).min(source_width - 1);
+            let index = (source_y * source_width + source_x) * 4;
+
+            bytes.extend_from_slice(&image.bytes[index..index + 4]);
+        }
+    }
+
+    Image {
+        bytes,
+        width: width as u16,
+        height: height as u16,
+    }
+}
+
+/// Tiles `frames`, stored top to bottom, into one roughly square image with bottom up
+/// rows like the ones `Image::export_png` expects.
+fn sprite_sheet(frames: &[Image]) -> Image {
+    let (tile_width, tile_height) = (frames[0].width as usize, frames[0].height as usize);
+
+    let columns = (frames.len() as f32).sqrt().ceil() as usize;
+    let rows = frames.len().div_ceil(columns);
+
+    let (width, height) = (tile_width * columns, tile_height * rows);
+    let mut bytes = vec![0; width * height * 4];
+
+    for (i, frame) in frames.iter().enumerate() {
+        let (column, row) = (i % columns, i / columns);
+
+        for y in 0..tile_height {
+            // NOTE: Counted from the bottom of the sheet
+            let sheet_y = height - 1 - (row * tile_height + y);
+            let start = (sheet_y * width + column * tile_width) * 4;
+            let source_start = y * tile_width * 4;
+
+            bytes[start..start + tile_width * 4]
+                .copy_from_slice(&frame.bytes[source_start..source_start + tile_width * 4]);
+        }
+    }
+
+    Image {
+        bytes,
+        width: width as u16,
+        height: height as u16,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One pixel per value, with the value in the red channel
+    fn image(width: u16, height: u16, values: &[u8]) -> Image {
+        assert_eq!(values.len(), width as usize * height as usize);
+
+        Image {
+            bytes: values
+                .iter()
+                .flat_map(|value| [*value, 0, 0, 255])
+                .collect(),
+            width,
+            height,
+        }
+    }
+
+    fn values(image: &Image) -> Vec<u8> {
+        image.bytes.chunks(4).map(|pixel| pixel[0]).collect()
+    }
+
+    fn temp_recorder(name: &str) -> Recorder {
+        Recorder {
+            interval: 1,
+            directory: std::env::temp_dir()
+                .join(format!("rust_2d_macro_{}_{name}", std::process::id())),
+            ..Default::default()
+        }
+    }
+
+    fn grab(_target: &mut Option<Texture2D>) -> Image {
+        image(2, 2, &[1, 2, 3, 4])
+    }
+
+    #[test]
+    fn the_toggle_starts_or_stops_a_recording_never_both() {
+        let mut recorder = temp_recorder("toggle");
+        let delta = 1.0 / 60.0;
+
+        assert_eq!(recorder.update_with(true, delta, grab).unwrap(), None);
+        assert!(recorder.is_recording());
+
+        for _ in 0..3 {
+            assert_eq!(recorder.update_with(false, delta, grab).unwrap(), None);
+        }
+        assert_eq!(recorder.frames.len(), 4);
+
+        let saved = recorder.update_with(true, delta, grab).unwrap();
+        assert!(saved.is_some());
+        assert!(!recorder.is_recording());
+        assert!(recorder.frames.is_empty());
+
+        // NOTE: Not recording, so nothing is grabbed
+        assert_eq!(
+            recorder.update_with(false, delta, |_| panic!()).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn full_recordings_stop_by_themselves() {
+        let mut recorder = Recorder {
+            max_frames: 3,
+            ..temp_recorder("full")
+        };
+
+        recorder.update_with(true, 0.0, grab).unwrap();
+        recorder.update_with(false, 0.0, grab).unwrap();
+        assert!(recorder.is_recording());
+
+        assert!(recorder.update_with(false, 0.0, grab).unwrap().is_some());
+        assert!(!recorder.is_recording());
+
+        let mut recorder = Recorder {
+            duration: 0.1,
+            ..temp_recorder("timed")
+        };
+        recorder.update_with(true, 0.05, grab).unwrap();
+        assert!(recorder.update_with(false, 0.05, grab).unwrap().is_some());
+    }
+
+    #[test]
+    fn only_every_interval_th_frame_is_captured() {
+        let mut recorder = Recorder {
+            interval: 3,
+            ..temp_recorder("interval")
+        };
+
+        recorder.update_with(true, 0.0, grab).unwrap();
+        for _ in 0..6 {
+            recorder.update_with(false, 0.0, grab).unwrap();
+        }
+        assert_eq!(recorder.frames.len(), 3);
+    }
+
+    #[test]
+    fn downsampling_picks_every_nth_pixel_and_flips_the_rows() {
+        // NOTE: Bottom up, so 5..=8 is the top row
+        let source = image(4, 2, &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        let half = downsample(&source, 2);
+        assert_eq!((half.width, half.height), (2, 1));
+        assert_eq!(values(&half), [5, 7]);
+
+        let same = downsample(&source, 1);
+        assert_eq!(values(&same), [5, 6, 7, 8, 1, 2, 3, 4]);
+
+        let tiny = downsample(&source, 8);
+        assert_eq!((tiny.width, tiny.height), (1, 1));
+    }
+
+    #[test]
+    fn sprite_sheets_tile_the_frames_left_to_right_and_top_to_bottom() {
+        let frames = [
+            image(1, 2, &[1, 2]),
+            image(1, 2, &[3, 4]),
+            image(1, 2, &[5, 6]),
+        ];
+
+        let sheet = sprite_sheet(&frames);
+        assert_eq!((sheet.width, sheet.height), (2, 4));
+
+        // NOTE: The sheet is bottom up and the frames top to bottom
+        assert_eq!(values(&sheet), [6, 0, 5, 0, 2, 4, 1, 3]);
+    }
+}
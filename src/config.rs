@@ -0,0 +1,202 @@
+/*
+    Config files
+
+    Plain `key=value` lines in the same spirit as `.env`. Blank lines and lines
+    starting with `#` are ignored.
+*/
+
+use std::{fs, io, path::Path, str::FromStr};
+
+use macroquad::prelude::KeyCode;
+
+// `Config` and `CONFIG`, generated by `build.rs` from the keys of the `.env` files
+include!(concat!(env!("OUT_DIR"), "/config.rs"));
+
+/// Yields every `(key, value)` pair, or `Err(line)` for the first line that is not one.
+pub fn parse(source: &str) -> impl Iterator<Item = Result<(&str, &str), &str>> {
+    source
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| match line.split_once('=') {
+            Some((key, value)) => Ok((key.trim(), value.trim())),
+            None => Err(line),
+        })
+}
+
+pub fn keycode_name(key_code: KeyCode) -> String {
+    format!("{key_code:?}")
+}
+
+pub fn keycode_from_name(name: &str) -> Option<KeyCode> {
+    (0..=KeyCode::Unknown as u32)
+        .map(|discriminant| {
+            /*
+                SAFETY: `KeyCode` is `#[repr(u32)]` with implicit, and therefore contiguous,
+                        discriminants from `Space` (0) up to `Unknown`, its last variant.
+            */
+            unsafe { std::mem::transmute::<u32, KeyCode>(discriminant) }
+        })
+        .find(|key_code| keycode_name(*key_code) == name)
+}
+
+pub const SETTINGS_PATH: &str = "settings.cfg";
+
+/// Everything the player can configure outside of key bindings.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    // Audio, both in `0.0..=1.0`
+    pub master_volume: f32,
+    pub music_volume: f32,
+
+    /*
+        Window
+
+        NOTE: These only take effect on the next start since the window is created
+              before the game. miniquad 0.3 has no way to toggle vsync, so there is
+              no setting for it.
+    */
+    pub fullscreen: bool,
+    pub window_width: i32,
+    pub window_height: i32,
+
+    // Debug
+    pub show_contacts: bool,
+    pub show_velocities: bool,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.8,
+
+            fullscreen: false,
+            window_width: 800,
+            window_height: 600,
+
+            show_contacts: false,
+            show_velocities: false,
+        }
+    }
+}
+
+impl Settings {
+    /// Reads the settings at `path`, falling back to the default for anything
+    /// missing or unreadable, including the file itself.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let mut settings = Self::default();
+
+        if let Ok(source) = fs::read_to_string(path) {
+            settings.merge(&source);
+        }
+
+        settings
+    }
+
+    /// Overrides every setting given in `source`, ignoring unknown keys and bad values.
+    pub fn merge(&mut self, source: &str) {
+        fn set<T: FromStr>(field: &mut T, value: &str) {
+            if let Ok(value) = value.parse() {
+                *field = value;
+            }
+        }
+
+        for (key, value) in parse(source).flatten() {
+            match key {
+                "master_volume" => set(&mut self.master_volume, value),
+                "music_volume" => set(&mut self.music_volume, value),
+
+                "fullscreen" => set(&mut self.fullscreen, value),
+                "window_width" => set(&mut self.window_width, value),
+                "window_height" => set(&mut self.window_height, value),
+
+                "show_contacts" => set(&mut self.show_contacts, value),
+                "show_velocities" => set(&mut self.show_velocities, value),
+
+                _ => {}
+            }
+        }
+
+        let defaults = Self::default();
+        for (volume, default) in [
+            (&mut self.master_volume, defaults.master_volume),
+            (&mut self.music_volume, defaults.music_volume),
+        ] {
+            *volume = if volume.is_nan() {
+                default
+            } else {
+                volume.clamp(0.0, 1.0)
+            };
+        }
+
+        self.window_width = self.window_width.max(1);
+        self.window_height = self.window_height.max(1);
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let output = format!(
+            "# Settings, one `key=value` per line\n\
+             master_volume={}\n\
+             music_volume={}\n\
+             fullscreen={}\n\
+             window_width={}\n\
+             window_height={}\n\
+             show_contacts={}\n\
+             show_velocities={}\n",
+            self.master_volume,
+            self.music_volume,
+            self.fullscreen,
+            self.window_width,
+            self.window_height,
+            self.show_contacts,
+            self.show_velocities,
+        );
+
+        fs::write(path, output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn partial_settings_keep_the_defaults_for_the_rest() {
+        let path =
+            std::env::temp_dir().join(format!("rust_2d_macro_{}_settings.cfg", std::process::id()));
+        fs::write(
+            &path,
+            "# only some\nmusic_volume = 0.3\nshow_contacts=true\n",
+        )
+        .unwrap();
+
+        let settings = Settings::load(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(
+            settings,
+            Settings {
+                music_volume: 0.3,
+                show_contacts: true,
+                ..Settings::default()
+            }
+        );
+        assert_eq!(Settings::load(&path), Settings::default());
+    }
+
+    #[test]
+    fn bad_settings_values_are_ignored_or_clamped() {
+        let mut settings = Settings::default();
+        settings.merge("master_volume=NaN\nmusic_volume=3\nfullscreen=yes\nwindow_width=-5\nbogus");
+
+        assert_eq!(
+            settings,
+            Settings {
+                music_volume: 1.0,
+                window_width: 1,
+                ..Settings::default()
+            }
+        );
+    }
+}
@@ -0,0 +1,212 @@
+/*
+    Debug console
+
+    NOTE: Only parsing and the text buffers live here, `Game::execute` maps the
+          parsed commands onto the game.
+*/
+
+use std::collections::VecDeque;
+
+use macroquad::prelude::*;
+
+// Lines of output kept around
+const MAX_OUTPUT: usize = 64;
+
+pub const HELP: &str = "commands: spawn ball|box [<x> <y>], gravity <x> <y>, wind <x> <y>, \
+                        timescale <scale>, dump, clear, help";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpawnShape {
+    Ball,
+    Box,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConsoleCommand {
+    // Under the mouse when `position` is `None`
+    Spawn {
+        shape: SpawnShape,
+        position: Option<Vec2>,
+    },
+    Gravity(Vec2),
+    Wind(Vec2),
+    TimeScale(f32),
+    Dump,
+    Clear,
+    Help,
+}
+
+/// Parses a line like `spawn ball 100 200` into a command, or an error to print.
+pub fn parse(line: &str) -> Result<ConsoleCommand, String> {
+    let mut words = line.split_whitespace();
+    let name = words.next().ok_or_else(|| "empty command".to_owned())?;
+    let args = words.collect::<Vec<_>>();
+
+    let number = |index: usize| -> Result<f32, String> {
+        let arg = args
+            .get(index)
+            .ok_or_else(|| format!("{name}: missing argument {}", index + 1))?;
+
+        arg.parse()
+            .map_err(|_| format!("{name}: '{arg}' is not a number"))
+    };
+
+    let vector =
+        |first: usize| -> Result<Vec2, String> { Ok(vec2(number(first)?, number(first + 1)?)) };
+
+    let command = match name {
+        "spawn" => {
+            let shape = match args.first().copied() {
+                Some("ball") => SpawnShape::Ball,
+                Some("box") => SpawnShape::Box,
+                Some(other) => return Err(format!("spawn: unknown shape '{other}'")),
+                None => return Err("spawn: missing shape".to_owned()),
+            };
+
+            ConsoleCommand::Spawn {
+                shape,
+                position: if args.len() > 1 {
+                    Some(vector(1)?)
+                } else {
+                    None
+                },
+            }
+        }
+
+        "gravity" => ConsoleCommand::Gravity(vector(0)?),
+        "wind" => ConsoleCommand::Wind(vector(0)?),
+        "timescale" => ConsoleCommand::TimeScale(number(0)?),
+        "dump" => ConsoleCommand::Dump,
+        "clear" => ConsoleCommand::Clear,
+        "help" => ConsoleCommand::Help,
+
+        _ => return Err(format!("unknown command '{name}', try 'help'")),
+    };
+
+    Ok(command)
+}
+
+#[derive(Debug, Default)]
+pub struct Console {
+    pub open: bool,
+    input: String,
+    output: VecDeque<String>,
+}
+
+impl Console {
+    pub fn print(&mut self, line: impl Into<String>) {
+        if self.output.len() == MAX_OUTPUT {
+            self.output.pop_front();
+        }
+
+        self.output.push_back(line.into());
+    }
+
+    pub fn clear(&mut self) {
+        self.output.clear();
+    }
+
+    /// Reads this frame's typing and returns the line submitted with Enter, if any.
+    pub fn read_input(&mut self) -> Option<String> {
+        while let Some(character) = get_char_pressed() {
+            // NOTE: The key toggling the console also arrives as a character
+            if !character.is_control() && character != '`' && character != '~' {
+                self.input.push(character);
+            }
+        }
+
+        if is_key_pressed(KeyCode::Backspace) {
+            self.input.pop();
+        }
+
+        if is_key_pressed(KeyCode::Enter) && !self.input.trim().is_empty() {
+            let line = std::mem::take(&mut self.input);
+            self.print(format!("> {line}"));
+            return Some(line);
+        }
+
+        None
+    }
+
+    /// Draws the output and input line across the top of the screen, in screen space.
+    pub fn render(&self) {
+        if !self.open {
+            return;
+        }
+
+        const FONT_SIZE: f32 = 18.0;
+        const LINES: usize = 12;
+
+        let height = FONT_SIZE * (LINES as f32 + 1.5);
+        draw_rectangle(
+            0.0,
+            0.0,
+            screen_width(),
+            height,
+            Color::new(0.0, 0.0, 0.0, 0.8),
+        );
+
+        let shown = self.output.len().min(LINES);
+        let mut y = FONT_SIZE;
+        for line in self.output.iter().skip(self.output.len() - shown) {
+            draw_text(line, 4.0, y, FONT_SIZE, LIGHTGRAY);
+            y += FONT_SIZE;
+        }
+
+        draw_text(
+            &format!("> {}_", self.input),
+            4.0,
+            height - FONT_SIZE * 0.5,
+            FONT_SIZE,
+            WHITE,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn commands_parse_with_their_arguments() {
+        assert_eq!(
+            parse("spawn ball 100 -200.5"),
+            Ok(ConsoleCommand::Spawn {
+                shape: SpawnShape::Ball,
+                position: Some(vec2(100.0, -200.5)),
+            })
+        );
+        assert_eq!(
+            parse("  spawn   box "),
+            Ok(ConsoleCommand::Spawn {
+                shape: SpawnShape::Box,
+                position: None,
+            })
+        );
+        assert_eq!(
+            parse("gravity 0 9.81"),
+            Ok(ConsoleCommand::Gravity(vec2(0.0, 9.81)))
+        );
+        assert_eq!(
+            parse("wind -5 0"),
+            Ok(ConsoleCommand::Wind(vec2(-5.0, 0.0)))
+        );
+        assert_eq!(parse("timescale 0.5"), Ok(ConsoleCommand::TimeScale(0.5)));
+        assert_eq!(parse("dump"), Ok(ConsoleCommand::Dump));
+        assert_eq!(parse("clear"), Ok(ConsoleCommand::Clear));
+        assert_eq!(parse("help"), Ok(ConsoleCommand::Help));
+    }
+
+    #[test]
+    fn bad_commands_explain_what_is_wrong() {
+        let error = |line: &str| parse(line).unwrap_err();
+
+        assert_eq!(error(""), "empty command");
+        assert_eq!(error("bogus 1"), "unknown command 'bogus', try 'help'");
+        assert_eq!(error("spawn"), "spawn: missing shape");
+        assert_eq!(error("spawn cat"), "spawn: unknown shape 'cat'");
+        assert_eq!(error("spawn ball 1"), "spawn: missing argument 3");
+        assert_eq!(error("gravity 0 down"), "gravity: 'down' is not a number");
+        assert_eq!(error("timescale"), "timescale: missing argument 1");
+    }
+}
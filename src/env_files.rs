@@ -0,0 +1,219 @@
+/*
+    .env files
+
+    NOTE: `build.rs` includes this file with `#[path]`, so it can only use `std`. The
+          crate only compiles it for its tests.
+*/
+
+use std::fmt::Write;
+
+// Read in order, keys in later files override the same keys in earlier ones
+pub const ENV_FILES: [&str; 2] = [".env", ".env.local"];
+
+/// Every `(key, value)` pair of `env_file`, or an error naming the first line that is not one.
+pub fn parse_env_file<'a>(
+    path: &str,
+    env_file: &'a str,
+) -> Result<Vec<(&'a str, &'a str)>, String> {
+    env_file
+        .lines()
+        .enumerate()
+        .map(|(i, line)| (i + 1, line.trim()))
+        .filter(|(_number, line)| !line.is_empty() && !line.starts_with('#'))
+        .map(|(number, line)| match line.split_once('=') {
+            Some((key, value)) => Ok((key.trim(), value.trim())),
+            None => Err(format!(
+                "{path}:{number}: expected KEY=value, found '{line}'"
+            )),
+        })
+        .collect()
+}
+
+/// Every key of `env_files` once, with the value from the last file setting it. Keys
+/// keep the place they were first set at.
+pub fn merge_env_files<'a>(
+    env_files: &[(&str, &'a str)],
+) -> Result<Vec<(&'a str, &'a str)>, String> {
+    let mut merged: Vec<(&str, &str)> = vec![];
+
+    for (path, env_file) in env_files {
+        for (key, value) in parse_env_file(path, env_file)? {
+            match merged.iter_mut().find(|(other, _)| *other == key) {
+                Some(entry) => entry.1 = value,
+                None => merged.push((key, value)),
+            }
+        }
+    }
+
+    Ok(merged)
+}
+
+/*
+    The Rust type and literal of `value`, trying `bool`, `i64` and `f64` before falling
+    back to a string.
+
+    NOTE: Only finite floats count, `inf` and `NaN` have no literal and stay strings.
+*/
+pub fn typed_value(value: &str) -> (&'static str, String) {
+    if let Ok(value) = value.parse::<bool>() {
+        return ("bool", format!("{value}"));
+    }
+
+    if let Ok(value) = value.parse::<i64>() {
+        return ("i64", format!("{value}"));
+    }
+
+    match value.parse::<f64>() {
+        Ok(float) if float.is_finite() => ("f64", format!("{float:?}")),
+        _ => ("&'static str", format!("{value:?}")),
+    }
+}
+
+/// Source defining `Config` with one field per key and the `CONFIG` holding the values.
+pub fn generate_config(entries: &[(&str, &str)]) -> Result<String, String> {
+    let mut fields = vec![];
+
+    for (key, value) in entries {
+        let field = key.to_lowercase();
+
+        let valid = field.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+            && field.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        if !valid {
+            return Err(format!(
+                "the key '{key}' can't be a field name, use letters, digits and '_'"
+            ));
+        }
+
+        if let Some((other, _, _)) = fields.iter().find(|(_, other, _)| *other == field) {
+            return Err(format!(
+                "the keys '{other}' and '{key}' both become '{field}'"
+            ));
+        }
+
+        fields.push((*key, field, typed_value(value)));
+    }
+
+    let mut source = String::new();
+
+    // NOTE: Writing to a `String` can't fail
+    writeln!(
+        source,
+        "/// Every key of {}, typed by its value.",
+        ENV_FILES.join(" and ")
+    )
+    .unwrap();
+    writeln!(source, "#[derive(Debug, Clone, Copy, PartialEq)]").unwrap();
+    writeln!(source, "pub struct Config {{").unwrap();
+    for (key, field, (ty, _literal)) in &fields {
+        writeln!(source, "    /// `{key}`").unwrap();
+        writeln!(source, "    pub {field}: {ty},").unwrap();
+    }
+    writeln!(source, "}}").unwrap();
+
+    writeln!(source).unwrap();
+
+    writeln!(source, "pub const CONFIG: Config = Config {{").unwrap();
+    for (_key, field, (_ty, literal)) in &fields {
+        writeln!(source, "    {field}: {literal},").unwrap();
+    }
+    writeln!(source, "}};").unwrap();
+
+    Ok(source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // NOTE: Compiled here as well, so a generated file that doesn't build fails the tests
+    mod sample {
+        include!("../tests/data/sample_config.rs");
+    }
+
+    #[test]
+    fn later_files_override_earlier_keys() {
+        let [base, local] = ENV_FILES;
+        let merged = merge_env_files(&[(base, "A=1\nB=2\nC=3"), (local, "B=20\nD=4")]);
+
+        assert_eq!(
+            merged,
+            Ok(vec![("A", "1"), ("B", "20"), ("C", "3"), ("D", "4")])
+        );
+    }
+
+    #[test]
+    fn repeated_keys_are_emitted_once() {
+        let merged = merge_env_files(&[(".env", "A=1\nA=2"), (".env.local", "A=3\nA=4")]);
+        assert_eq!(merged, Ok(vec![("A", "4")]));
+    }
+
+    #[test]
+    fn comments_blank_lines_and_spaces_are_ignored() {
+        let parsed = parse_env_file(".env", "# comment\n\n  KEY = some value  \n");
+        assert_eq!(parsed, Ok(vec![("KEY", "some value")]));
+    }
+
+    #[test]
+    fn malformed_lines_name_the_file_and_line() {
+        let merged = merge_env_files(&[(".env", "A=1"), (".env.local", "\nB=2\noops")]);
+        assert_eq!(
+            merged,
+            Err(".env.local:3: expected KEY=value, found 'oops'".to_owned())
+        );
+    }
+
+    #[test]
+    fn sample_env_files_generate_the_expected_config() {
+        let env_files = [
+            (".env", include_str!("../tests/data/sample.env")),
+            (".env.local", include_str!("../tests/data/sample.env.local")),
+        ];
+
+        let entries = merge_env_files(&env_files).unwrap();
+        let source = generate_config(&entries).unwrap();
+
+        assert_eq!(source, include_str!("../tests/data/sample_config.rs"));
+        assert_eq!(
+            sample::CONFIG,
+            sample::Config {
+                window_title: "rust 2d macro",
+                window_width: 1920,
+                vsync: true,
+                gravity: 9.81,
+            }
+        );
+    }
+
+    #[test]
+    fn values_are_typed_by_what_they_parse_as() {
+        assert_eq!(typed_value("false"), ("bool", "false".to_owned()));
+        assert_eq!(typed_value("-3"), ("i64", "-3".to_owned()));
+        assert_eq!(typed_value("2"), ("i64", "2".to_owned()));
+        assert_eq!(typed_value("2.0"), ("f64", "2.0".to_owned()));
+        assert_eq!(typed_value("inf"), ("&'static str", "\"inf\"".to_owned()));
+        assert_eq!(
+            typed_value("a \"b\""),
+            ("&'static str", "\"a \\\"b\\\"\"".to_owned())
+        );
+    }
+
+    #[test]
+    fn keys_which_are_no_field_names_fail_the_build() {
+        assert_eq!(
+            generate_config(&[("A-B", "1")]),
+            Err("the key 'A-B' can't be a field name, use letters, digits and '_'".to_owned())
+        );
+        assert_eq!(
+            generate_config(&[("1A", "1")]),
+            Err("the key '1A' can't be a field name, use letters, digits and '_'".to_owned())
+        );
+    }
+
+    #[test]
+    fn keys_differing_only_in_case_fail_the_build() {
+        assert_eq!(
+            generate_config(&[("FOO", "1"), ("foo", "2")]),
+            Err("the keys 'FOO' and 'foo' both become 'foo'".to_owned())
+        );
+    }
+}
@@ -0,0 +1,9119 @@
+use std::{
+    cell::Cell,
+    collections::{BTreeSet, HashMap, VecDeque},
+    f32::consts::PI,
+    fs,
+    hash::{Hash, Hasher},
+    io,
+    ops::Range,
+    path::Path,
+};
+
+use bitsets::{BitSet, Flag};
+use macroquad::prelude::*;
+#[cfg(feature = "physics")]
+use rapier2d::prelude::*;
+use renderer::{MacroquadRenderer, Renderer};
+#[cfg(feature = "physics")]
+use std::collections::BTreeMap;
+
+#[cfg(feature = "physics")]
+use slotmap::KeyData;
+use slotmap::{new_key_type, DenseSlotMap, Key, SecondaryMap, SparseSecondaryMap};
+
+pub mod bitsets;
+pub mod capture;
+pub mod config;
+pub mod console;
+#[cfg(test)]
+mod env_files;
+pub mod menu;
+pub mod profiler;
+pub mod renderer;
+pub mod toast;
+pub mod tween;
+pub mod utils;
+
+new_key_type! {
+    pub struct Entity;
+}
+
+#[rustfmt::skip]
+pub mod components {
+    use crate::bitsets::{BitSet, Flag};
+
+    pub type Query = BitSet;
+
+    pub const TEXTURE: Flag         = BitSet::flag(0);
+    pub const RIGIDBODY: Flag       = BitSet::flag(1);
+    pub const COLLIDER: Flag        = BitSet::flag(2);
+    pub const FIXED_COLLIDER: Flag  = BitSet::flag(3);
+    pub const PLAYER: Flag          = BitSet::flag(4);
+    pub const TRIGGER: Flag         = BitSet::flag(5);
+    pub const CHECKPOINT: Flag      = BitSet::flag(6);
+    pub const TRAIL: Flag           = BitSet::flag(7);
+    pub const DISABLED: Flag        = BitSet::flag(8);
+    pub const DRAG_ZONE: Flag       = BitSet::flag(9);
+    pub const TILEMAP: Flag         = BitSet::flag(10);
+    pub const CONVEYOR: Flag        = BitSet::flag(11);
+    pub const NET_INTERP: Flag      = BitSet::flag(12);
+    pub const SCREEN_SPACE: Flag    = BitSet::flag(13);
+    pub const HOMING: Flag          = BitSet::flag(14);
+    pub const SCRIPT: Flag          = BitSet::flag(15);
+    pub const TIMER: Flag           = BitSet::flag(16);
+
+    pub const NUM_COMPONENTS: usize =     17;
+
+    pub fn every_component() -> impl Iterator<Item=Flag> + 'static  {
+        (0..NUM_COMPONENTS as u32).map(BitSet::flag)
+    }
+
+    pub fn name(flag: Flag) -> Option<&'static str> {
+        match flag {
+            TEXTURE         => Some("TEXTURE"),
+            RIGIDBODY       => Some("RIGIDBODY"),
+            COLLIDER        => Some("COLLIDER"),
+            FIXED_COLLIDER  => Some("FIXED_COLLIDER"),
+            PLAYER          => Some("PLAYER"),
+            TRIGGER         => Some("TRIGGER"),
+            CHECKPOINT      => Some("CHECKPOINT"),
+            TRAIL           => Some("TRAIL"),
+            DISABLED        => Some("DISABLED"),
+            DRAG_ZONE       => Some("DRAG_ZONE"),
+            TILEMAP         => Some("TILEMAP"),
+            CONVEYOR        => Some("CONVEYOR"),
+            NET_INTERP      => Some("NET_INTERP"),
+            SCREEN_SPACE    => Some("SCREEN_SPACE"),
+            HOMING          => Some("HOMING"),
+            SCRIPT          => Some("SCRIPT"),
+            TIMER           => Some("TIMER"),
+            _               => None,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Flag> {
+        every_component().find(|flag| self::name(*flag) == Some(name))
+    }
+
+    /// The name of every set flag, lowest first, e.g. to `join("|")` for a level file.
+    pub fn flags_to_names(bitset: BitSet) -> Vec<&'static str> {
+        bitset.iter_flags().filter_map(name).collect()
+    }
+
+    /// The bitset with every named flag set, or `Err` with the first unknown name.
+    pub fn names_to_flags<'a>(names: &[&'a str]) -> Result<BitSet, &'a str> {
+        names.iter().try_fold(BitSet::empty(), |bitset, name| {
+            from_name(name.trim()).map(|flag| bitset.with(flag)).ok_or(*name)
+        })
+    }
+}
+
+use components::Query;
+
+#[derive(Clone)]
+pub struct TextureComponent {
+    pub texture: Texture2D,
+    pub size: Vec2,
+    pub color: Color,
+    // Only stretches the drawn sprite, e.g. for squash and stretch, anything else uses `size`
+    pub render_scale: Vec2,
+    // Radians added to the body's rotation, for art that doesn't face the collider's way
+    pub rotation_offset: f32,
+}
+
+impl TextureComponent {
+    /// The size the sprite is drawn at, around the same center as `size`.
+    #[inline]
+    pub fn draw_size(&self) -> Vec2 {
+        self.size * self.render_scale
+    }
+
+    /// The rotation the sprite is drawn at on a body rotated by `body_rotation`.
+    #[inline]
+    pub fn draw_rotation(&self, body_rotation: f32) -> f32 {
+        body_rotation + self.rotation_offset
+    }
+}
+
+#[derive(Clone)]
+#[cfg(feature = "physics")]
+pub struct RigidbodyComponent {
+    rigidbody_handle: RigidBodyHandle,
+
+    /*
+        NOTE: rapier 0.14 can't report which single axes are locked, so the locks are
+              tracked here. A body built with only one translation axis locked shows
+              up with neither locked.
+    */
+    locked_axes: LockedAxes,
+
+    // Where the body was before the latest logic tick, rendering blends from here
+    previous_position: Isometry<Real>,
+}
+
+/*
+    NOTE: The first collider is the entity's main one, which single shape features like
+          triggers, drag zones and conveyors use. Compound entities attach the rest
+          with `Game::add_extra_collider`.
+*/
+#[derive(Clone)]
+#[cfg(feature = "physics")]
+pub struct ColliderComponent {
+    collider_handles: Vec<ColliderHandle>,
+}
+
+#[cfg(feature = "physics")]
+impl ColliderComponent {
+    pub fn new(collider_handle: ColliderHandle) -> Self {
+        Self {
+            collider_handles: vec![collider_handle],
+        }
+    }
+
+    #[inline]
+    pub fn collider_handle(&self) -> ColliderHandle {
+        self.collider_handles[0]
+    }
+}
+
+// What `Game::set_collider_scale` scales from, captured the first time an entity is scaled
+#[derive(Clone)]
+#[cfg(feature = "physics")]
+struct ColliderScale {
+    scale: Vec2,
+    // The shape and offset from the body of every collider, in `collider_handles` order
+    shapes: Vec<(SharedShape, Isometry<Real>)>,
+    texture_size: Option<Vec2>,
+}
+
+/*
+    Surface coefficients for `Game::add_material`. When two colliders touch, rapier picks
+    the rule to combine their coefficients by from the pair, going with the later of
+    `Average`, `Min`, `Multiply` and `Max`. Defaults to what rapier gives a new collider.
+*/
+#[cfg(feature = "physics")]
+#[derive(Debug, Clone, Copy)]
+pub struct Material {
+    pub restitution: f32,
+    pub friction: f32,
+    pub restitution_combine: CoefficientCombineRule,
+    pub friction_combine: CoefficientCombineRule,
+}
+
+#[cfg(feature = "physics")]
+impl Default for Material {
+    fn default() -> Self {
+        Self {
+            restitution: 0.0,
+            friction: ColliderBuilder::default_friction(),
+            restitution_combine: CoefficientCombineRule::Average,
+            friction_combine: CoefficientCombineRule::Average,
+        }
+    }
+}
+
+/// What the physics is busy with as of the last step, see `Game::physics_stats`.
+#[cfg(feature = "physics")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PhysicsStats {
+    // Dynamic and kinematic bodies which aren't asleep
+    pub active_bodies: usize,
+    // Collider pairs the broad phase found close enough to check, touching or not
+    pub broad_phase_pairs: usize,
+    // Pairs of solid colliders with at least one active contact
+    pub contact_pairs: usize,
+    // Pairs involving a sensor which overlap
+    pub intersection_pairs: usize,
+}
+
+// What a disabled entity's physics looked like before it was frozen
+#[derive(Clone)]
+pub struct DisabledComponent {
+    #[cfg(feature = "physics")]
+    body_type: RigidBodyType,
+    #[cfg(feature = "physics")]
+    linvel: nalgebra::Vector2<f32>,
+    #[cfg(feature = "physics")]
+    angvel: f32,
+    // Collision and solver groups of each collider, in the order of `ColliderComponent`
+    #[cfg(feature = "physics")]
+    collider_groups: Vec<(InteractionGroups, InteractionGroups)>,
+}
+
+/// Knobs for `player_movement_system`, velocities are in world units per second.
+#[cfg_attr(not(feature = "physics"), allow(dead_code))]
+#[derive(Debug, Clone, Copy)]
+struct PlayerTuning {
+    speed: f32,
+    jump_velocity: f32,
+
+    // Fastest fall while airborne and pressing into a wall
+    wall_slide_speed: f32,
+    // Velocity after a wall jump, `x` away from the wall and `y` up
+    wall_jump_velocity: Vec2,
+
+    dash_speed: f32,
+    // Seconds between the start of one dash and the next
+    dash_cooldown: f32,
+    // Seconds `PlayerComponent::is_dashing` stays true after a dash, e.g. for i-frames
+    dash_duration: f32,
+
+    /*
+        Upper bound on the physics substeps taken while a player moves more than half
+        its own size in one tick, which is when it could tunnel through thin walls.
+        `1` turns substepping off.
+    */
+    max_substeps: usize,
+
+    // The render scale a landing squashes and a jump stretches the sprite to
+    land_squash: Vec2,
+    jump_stretch: Vec2,
+    // Seconds it takes to ease back to the sprite's own shape
+    squash_duration: f32,
+}
+
+/// Knobs for `camera_follow_system`, distances are in world units.
+#[derive(Debug, Clone, Copy)]
+struct CameraTuning {
+    // Half the size of the box around the camera the target can move in freely
+    dead_zone: Vec2,
+
+    // Seconds of the target's velocity the camera looks ahead by, up to `max_look_ahead`
+    look_ahead: f32,
+    max_look_ahead: f32,
+
+    // How quickly the camera catches up, per second
+    follow_speed: Vec2,
+}
+
+impl Default for CameraTuning {
+    fn default() -> Self {
+        Self {
+            dead_zone: vec2(30.0, 50.0),
+
+            look_ahead: 0.2,
+            max_look_ahead: 150.0,
+
+            follow_speed: vec2(10.0, 5.0),
+        }
+    }
+}
+
+impl Default for PlayerTuning {
+    fn default() -> Self {
+        Self {
+            speed: 1_000.0,
+            jump_velocity: 800.0,
+
+            wall_slide_speed: 100.0,
+            wall_jump_velocity: vec2(400.0, 700.0),
+
+            dash_speed: 1_500.0,
+            dash_cooldown: 0.8,
+            dash_duration: 0.15,
+
+            max_substeps: 8,
+
+            land_squash: vec2(1.3, 0.7),
+            jump_stretch: vec2(0.8, 1.25),
+            squash_duration: 0.2,
+        }
+    }
+}
+
+/// What the player asked for, read from the keyboard once per frame by `player_input_system`.
+#[cfg_attr(not(feature = "physics"), allow(dead_code))]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlayerInput {
+    // In world space, so "right" on screen is negative x
+    movement: Vec2,
+    jump: bool,
+    dash: bool,
+}
+
+#[cfg_attr(not(feature = "physics"), allow(dead_code))]
+#[derive(Clone)]
+pub struct PlayerComponent {
+    respawn_point: Vec2,
+
+    // Sign of the last horizontal movement, in world space
+    facing: f32,
+
+    // Seconds left until the player can dash again
+    dash_cooldown: f32,
+    // Seconds left of the current dash
+    dash_timer: f32,
+
+    /*
+        Optional thin sensor below the player, see `Game::add_feet_sensor`, and whether it
+        overlapped something solid after the last step.
+
+        NOTE: Without a sensor `Game::is_grounded` looks at the contact normals instead,
+              which needs no setup but drops out for a tick whenever the body bounces
+              off the ground, and on steep slopes where the normal is more sideways than
+              `SURFACE_NORMAL_THRESHOLD` allows. The sensor keeps reporting ground on
+              slopes and ledges as long as it overlaps them, but it is a second collider
+              to size right: too tall and the player counts as grounded just above the
+              floor and next to walls.
+    */
+    #[cfg(feature = "physics")]
+    feet_sensor: Option<ColliderHandle>,
+    feet_grounded: bool,
+
+    // Grounded as of the last `squash_stretch_system`, to tell landings and jumps apart
+    was_grounded: bool,
+    // Bumped for every squash, so that an older tween stops writing the render scale
+    squash_generation: u32,
+}
+
+impl Default for PlayerComponent {
+    fn default() -> Self {
+        Self {
+            respawn_point: Vec2::ZERO,
+
+            // NOTE: World x is mirrored on screen, so this faces right
+            facing: -1.0,
+
+            dash_cooldown: 0.0,
+            dash_timer: 0.0,
+
+            #[cfg(feature = "physics")]
+            feet_sensor: None,
+            feet_grounded: false,
+
+            was_grounded: false,
+            squash_generation: 0,
+        }
+    }
+}
+
+impl PlayerComponent {
+    pub fn is_dashing(&self) -> bool {
+        self.dash_timer > 0.0
+    }
+}
+
+#[derive(Clone)]
+pub struct TriggerComponent {
+    id: u32,
+}
+
+/*
+    A fixed collider dragging whatever touches it along its surface.
+
+    NOTE: rapier 0.14 has no per-collider surface velocity, so `conveyor_system`
+          emulates one after each step by replacing the tangential part of the
+          velocity of every dynamic body touching the belt.
+*/
+#[cfg_attr(not(feature = "physics"), allow(dead_code))]
+#[derive(Clone)]
+pub struct ConveyorComponent {
+    surface_velocity: Vec2,
+}
+
+/*
+    Smooths out a kinematic body driven by network snapshots. `prev` and `next` are the
+    last two received `(position, rotation)` pairs, `next` arriving at `recv_time`.
+
+    NOTE: Playback runs one `NET_SNAPSHOT_INTERVAL` behind, gliding from `prev` to `next`
+          over the interval after `next` arrives. When the following snapshot is late,
+          the motion is extrapolated for up to `NET_MAX_EXTRAPOLATION` more intervals.
+*/
+#[derive(Clone)]
+pub struct NetInterpComponent {
+    prev: (Vec2, f32),
+    next: (Vec2, f32),
+    recv_time: f64,
+}
+
+impl NetInterpComponent {
+    pub fn new(position: Vec2, rotation: f32, time: f64) -> Self {
+        Self {
+            prev: (position, rotation),
+            next: (position, rotation),
+            recv_time: time,
+        }
+    }
+
+    pub fn push(&mut self, position: Vec2, rotation: f32, time: f64) {
+        self.prev = self.next;
+        self.next = (position, rotation);
+        self.recv_time = time;
+    }
+
+    pub fn sample(&self, time: f64) -> (Vec2, f32) {
+        let t = ((time - self.recv_time) / NET_SNAPSHOT_INTERVAL) as f32;
+        let t = if t.is_nan() {
+            0.0
+        } else {
+            t.clamp(0.0, 1.0 + NET_MAX_EXTRAPOLATION)
+        };
+
+        let ((prev_position, prev_rotation), (next_position, next_rotation)) =
+            (self.prev, self.next);
+
+        // NOTE: Take the short way around instead of spinning through +-pi
+        let turn = (next_rotation - prev_rotation + PI).rem_euclid(2.0 * PI) - PI;
+
+        (
+            prev_position.lerp(next_position, t),
+            prev_rotation + turn * t,
+        )
+    }
+}
+
+/*
+    Steers the body's velocity towards `target` by at most `turn_rate` radians per
+    second while keeping it at `speed`. Without a living target it picks the nearest
+    entity matching `targets`, and flies straight on when there is none.
+
+    NOTE: The projectile itself shouldn't match `targets` or it ends up chasing itself.
+*/
+#[cfg_attr(not(feature = "physics"), allow(dead_code))]
+#[derive(Clone)]
+pub struct HomingComponent {
+    turn_rate: f32,
+    speed: f32,
+    target: Option<Entity>,
+    targets: Query,
+}
+
+/*
+    Pins the entity's texture to the screen, e.g. for HUD elements. `anchor` is where
+    the center of the texture goes, in pixels from the top left corner of the window.
+*/
+#[derive(Clone)]
+pub struct ScreenSpaceComponent {
+    pub anchor: Vec2,
+}
+
+impl ScreenSpaceComponent {
+    /// Where a texture of `size` is drawn, regardless of the camera.
+    pub fn rect(&self, size: Vec2) -> Rect {
+        Rect::new(
+            self.anchor.x - size.x / 2.0,
+            self.anchor.y - size.y / 2.0,
+            size.x,
+            size.y,
+        )
+    }
+}
+
+/*
+    Named countdowns, e.g. for cooldowns, spawners or timed doors. `Game::timer_system`
+    counts every timer down to `0.0`, where it stays expired until it is set again or
+    reset. A timer which was never set, or was reset, is neither running nor expired.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct TimerComponent {
+    // Seconds left of every timer, `0.0` once expired
+    pub timers: HashMap<&'static str, f32>,
+}
+
+impl TimerComponent {
+    /// Starts `name` over with `duration` seconds left.
+    pub fn set(&mut self, name: &'static str, duration: f32) {
+        self.timers.insert(name, duration.max(0.0));
+    }
+
+    /// Forgets `name`, so it neither runs nor counts as expired.
+    pub fn reset(&mut self, name: &'static str) {
+        self.timers.remove(name);
+    }
+
+    pub fn remaining(&self, name: &str) -> Option<f32> {
+        self.timers.get(name).copied()
+    }
+
+    pub fn is_running(&self, name: &str) -> bool {
+        self.remaining(name)
+            .is_some_and(|remaining| remaining > 0.0)
+    }
+
+    pub fn is_expired(&self, name: &str) -> bool {
+        self.remaining(name) == Some(0.0)
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        self.timers
+            .values_mut()
+            .for_each(|remaining| *remaining = (*remaining - delta).max(0.0));
+    }
+}
+
+/*
+    Gameplay logic of a single entity which doesn't deserve a component and system of
+    its own. `Game::script_system` calls `update` once per tick for every enabled
+    entity with a `ScriptComponent`.
+
+    NOTE: Behaviors have to be `Clone` so that `Game::clone_world` can copy them, any
+          `#[derive(Clone)]` type gets `BehaviorClone` for free.
+*/
+pub trait EntityBehavior: BehaviorClone {
+    fn update(&mut self, ctx: &mut BehaviorCtx, entity: Entity, delta: f32);
+}
+
+pub trait BehaviorClone {
+    fn clone_box(&self) -> Box<dyn EntityBehavior>;
+}
+
+impl<T: EntityBehavior + Clone + 'static> BehaviorClone for T {
+    fn clone_box(&self) -> Box<dyn EntityBehavior> {
+        Box::new(self.clone())
+    }
+}
+
+impl Clone for Box<dyn EntityBehavior> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+#[derive(Clone)]
+pub struct ScriptComponent {
+    pub behavior: Box<dyn EntityBehavior>,
+}
+
+impl ScriptComponent {
+    pub fn new(behavior: impl EntityBehavior + 'static) -> Self {
+        Self {
+            behavior: Box::new(behavior),
+        }
+    }
+}
+
+/*
+    What a behavior may do to the game during `EntityBehavior::update`. Spawning and
+    despawning go through the command buffer like in any other system, so the entities
+    only change once every behavior has run.
+*/
+pub struct BehaviorCtx<'a> {
+    game: &'a mut Game,
+}
+
+impl BehaviorCtx<'_> {
+    #[cfg(feature = "physics")]
+    pub fn position(&self, entity: Entity) -> Option<Vec2> {
+        self.game.entity_position(entity)
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn set_position(&mut self, entity: Entity, position: Vec2) {
+        self.game.set_position(entity, position);
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn linvel(&self, entity: Entity) -> Option<Vec2> {
+        self.game.linvel(entity)
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn set_linvel(&mut self, entity: Entity, linvel: Vec2) {
+        self.game.set_linvel(entity, linvel);
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn apply_impulse(&mut self, entity: Entity, impulse: Vec2) {
+        self.game.apply_impulse(entity, impulse);
+    }
+
+    pub fn events(&self) -> &[GameEvent] {
+        self.game.events()
+    }
+
+    pub fn resources(&self) -> &Resources {
+        self.game.resources()
+    }
+
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.game.is_alive(entity)
+    }
+
+    pub fn set_timer(&mut self, entity: Entity, name: &'static str, duration: f32) {
+        self.game.set_timer(entity, name, duration);
+    }
+
+    pub fn timer_expired(&self, entity: Entity, name: &str) -> bool {
+        self.game.timer_expired(entity, name)
+    }
+
+    pub fn timer_running(&self, entity: Entity, name: &str) -> bool {
+        self.game.timer_running(entity, name)
+    }
+
+    /// Spawns a new entity once the behaviors have run and passes it to `init`.
+    pub fn spawn(&mut self, label: &'static str, init: impl FnOnce(&mut Game, Entity) + 'static) {
+        self.game.commands.spawn(label, init);
+    }
+
+    /// Removes `entity` at the end of the tick.
+    pub fn despawn(&mut self, entity: Entity) {
+        self.game.commands.despawn(entity);
+    }
+
+    /// Sets `flag` on `entity` once the behaviors have run.
+    pub fn add_flag(&mut self, entity: Entity, flag: Flag) {
+        self.game.commands.add_flag(entity, flag);
+    }
+
+    /// Clears `flag` on `entity` once the behaviors have run.
+    pub fn remove_flag(&mut self, entity: Entity, flag: Flag) {
+        self.game.commands.remove_flag(entity, flag);
+    }
+}
+
+/// A sensor region slowing down and lifting the bodies inside it, like water.
+#[cfg_attr(not(feature = "physics"), allow(dead_code))]
+#[derive(Clone)]
+pub struct DragZoneComponent {
+    /// Extra damping applied on top of the body's own, with the same meaning as rapier's
+    linear_damping: f32,
+    /// Upward acceleration, in the same units as `Game::gravity`
+    buoyancy: f32,
+}
+
+#[derive(Clone)]
+pub struct TrailComponent {
+    positions: VecDeque<Vec2>,
+    #[cfg_attr(not(feature = "physics"), allow(dead_code))]
+    max: usize,
+}
+
+impl TrailComponent {
+    pub fn new(max: usize) -> Self {
+        Self {
+            positions: VecDeque::with_capacity(max),
+            max,
+        }
+    }
+}
+
+/*
+    A grid of tiles stored row-major, `tiles[y * width + x]`, with tile (0, 0) at `origin`.
+
+    Tile `i` is the `i`th `tile_size` x `tile_size` pixel cell of `tileset`, counted
+    left to right and top to bottom.
+*/
+#[derive(Clone)]
+pub struct TilemapComponent {
+    tiles: Vec<Option<u16>>,
+    width: usize,
+    height: usize,
+    tile_size: f32,
+    tileset: Texture2D,
+    origin: Vec2,
+
+    // Generated by `Game::build_tilemap_colliders`, not attached to any rigidbody
+    #[cfg(feature = "physics")]
+    collider_handles: Vec<ColliderHandle>,
+}
+
+impl TilemapComponent {
+    pub fn new(width: usize, height: usize, tile_size: f32, tileset: Texture2D) -> Self {
+        Self {
+            tiles: vec![None; width * height],
+            width,
+            height,
+            tile_size,
+            tileset,
+            origin: Vec2::ZERO,
+
+            #[cfg(feature = "physics")]
+            collider_handles: vec![],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<u16> {
+        if x < self.width && y < self.height {
+            self.tiles[y * self.width + x]
+        } else {
+            None
+        }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, tile: Option<u16>) {
+        if x < self.width && y < self.height {
+            self.tiles[y * self.width + x] = tile;
+        }
+    }
+
+    /*
+        Covers every non-empty tile with as few `(x, y, width, height)` rectangles as
+        possible by greedily growing each one right and then down from its top-left
+        tile. Not always optimal, but a solid run always ends up as one rectangle.
+    */
+    pub fn solid_rects(&self) -> Vec<(usize, usize, usize, usize)> {
+        let mut covered = vec![false; self.tiles.len()];
+        let free = |covered: &[bool], x: usize, y: usize| {
+            let i = y * self.width + x;
+            self.tiles[i].is_some() && !covered[i]
+        };
+
+        let mut rects = vec![];
+
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if !free(&covered, x, y) {
+                    continue;
+                }
+
+                let mut width = 1;
+                while x + width < self.width && free(&covered, x + width, y) {
+                    width += 1;
+                }
+
+                let mut height = 1;
+                while y + height < self.height
+                    && (x..x + width).all(|column| free(&covered, column, y + height))
+                {
+                    height += 1;
+                }
+
+                for row in y..y + height {
+                    covered[row * self.width + x..row * self.width + x + width].fill(true);
+                }
+
+                rects.push((x, y, width, height));
+            }
+        }
+
+        rects
+    }
+
+    /// The columns and rows of the tiles which overlap `view`, clamped to the map.
+    pub fn visible_range(&self, view: Rect) -> (Range<usize>, Range<usize>) {
+        let range = |min: f32, max: f32, origin: f32, len: usize| {
+            let start = ((min - origin) / self.tile_size).floor().max(0.0) as usize;
+            let end = ((max - origin) / self.tile_size).ceil().max(0.0) as usize;
+
+            start.min(len)..end.min(len)
+        };
+
+        (
+            range(view.left(), view.right(), self.origin.x, self.width),
+            range(view.top(), view.bottom(), self.origin.y, self.height),
+        )
+    }
+}
+
+/*
+    NOTE: Events are gathered during a single logic tick. The events of the previous
+          tick are dropped at the start of `run_logic_systems`, so anything reading
+          `events()` after a tick sees exactly what happened during that tick.
+          Events emitted between ticks (e.g. by `kill`) are kept for the next one.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GameEvent {
+    TriggerEnter {
+        trigger: Entity,
+        entity: Entity,
+    },
+    TriggerExit {
+        trigger: Entity,
+        entity: Entity,
+    },
+
+    Died {
+        entity: Entity,
+    },
+    OutOfBounds {
+        entity: Entity,
+    },
+    // The body's velocity was NaN or infinite after the step and got zeroed
+    NonFiniteVelocity {
+        entity: Entity,
+    },
+
+    // Only emitted while enabled with `Game::set_flag_events`
+    FlagsChanged {
+        entity: Entity,
+        old: BitSet,
+        new: BitSet,
+    },
+}
+
+/// Game wide state that belongs to no entity in particular, shown by the HUD.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Resources {
+    pub score: u64,
+    // Lost when a player dies, the game is over once none are left
+    pub lives: u32,
+    // Seconds of simulation since the game started, scaled by `time_scale`
+    pub elapsed: f64,
+}
+
+impl Default for Resources {
+    fn default() -> Self {
+        Self {
+            score: 0,
+            lives: 3,
+            elapsed: 0.0,
+        }
+    }
+}
+
+/// Why a collider couldn't be built from the given points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShapeError {
+    // All points on one line, or fewer than three of them
+    DegenerateConvexHull,
+    // A line made of less than two points
+    TooFewPoints { got: usize, needed: usize },
+}
+
+impl std::fmt::Display for ShapeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ShapeError::DegenerateConvexHull => {
+                write!(
+                    f,
+                    "the points don't span an area, so they have no convex hull"
+                )
+            }
+
+            ShapeError::TooFewPoints { got, needed } => {
+                write!(f, "got {got} points, but at least {needed} are needed")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ShapeError {}
+
+/// A collider shaped like the convex hull of `points`, given relative to its body.
+#[cfg(feature = "physics")]
+pub fn convex_collider(points: &[Vec2]) -> Result<ColliderBuilder, ShapeError> {
+    let points = points
+        .iter()
+        .map(|point| point![point.x, point.y])
+        .collect::<Vec<_>>();
+
+    let builder = ColliderBuilder::convex_hull(&points).ok_or(ShapeError::DegenerateConvexHull)?;
+
+    // NOTE: parry happily returns a "hull" of two points for collinear input
+    let corners = builder
+        .shape
+        .as_convex_polygon()
+        .map_or(0, |hull| hull.points().len());
+
+    if corners < 3 {
+        return Err(ShapeError::DegenerateConvexHull);
+    }
+
+    Ok(builder)
+}
+
+/*
+    `shape` stretched by `scale` along each axis, or `None` for shapes that can't be,
+    like compounds.
+
+    NOTE: Balls and rounded borders stay round, so their radius follows the larger axis.
+*/
+#[cfg(feature = "physics")]
+fn scaled_shape(shape: &dyn Shape, scale: &Vector<Real>) -> Option<SharedShape> {
+    use rapier2d::parry::shape::{RoundShape, TypedShape};
+
+    let radius_scale = scale.x.max(scale.y);
+
+    let shape = match shape.as_typed_shape() {
+        TypedShape::Ball(ball) => SharedShape::ball(ball.radius * radius_scale),
+        TypedShape::Capsule(capsule) => {
+            let segment = capsule.segment.scaled(scale);
+            SharedShape::capsule(segment.a, segment.b, capsule.radius * radius_scale)
+        }
+
+        TypedShape::Cuboid(cuboid) => SharedShape::new(cuboid.scaled(scale)),
+        TypedShape::RoundCuboid(round) => SharedShape::new(RoundShape {
+            inner_shape: round.inner_shape.scaled(scale),
+            border_radius: round.border_radius * radius_scale,
+        }),
+
+        TypedShape::ConvexPolygon(polygon) => SharedShape::new(polygon.clone().scaled(scale)?),
+        TypedShape::RoundConvexPolygon(round) => SharedShape::new(RoundShape {
+            inner_shape: round.inner_shape.clone().scaled(scale)?,
+            border_radius: round.border_radius * radius_scale,
+        }),
+
+        TypedShape::Segment(segment) => SharedShape::new(segment.scaled(scale)),
+        TypedShape::Triangle(triangle) => SharedShape::new(triangle.scaled(scale)),
+        TypedShape::Polyline(polyline) => SharedShape::new(polyline.clone().scaled(scale)),
+        TypedShape::TriMesh(trimesh) => SharedShape::new(trimesh.clone().scaled(scale)),
+        TypedShape::HeightField(heightfield) => SharedShape::new(heightfield.clone().scaled(scale)),
+
+        _ => return None,
+    };
+
+    Some(shape)
+}
+
+/*
+    NOTE: Systems can't add or remove entities while they are iterating over them, so
+          they queue the changes in `Game::commands` instead. `run_logic_systems`
+          applies the queue in order after each system, except for despawns, which
+          all happen together once every logic system has run. An entity queued for
+          despawn therefore stays alive for the rest of the tick and rendering never
+          sees a half updated world.
+*/
+type SpawnFn = Box<dyn FnOnce(&mut Game, Entity)>;
+
+enum Command {
+    Spawn { label: &'static str, init: SpawnFn },
+    Despawn(Entity),
+    AddFlag(Entity, Flag),
+    RemoveFlag(Entity, Flag),
+}
+
+#[derive(Default)]
+struct CommandBuffer {
+    commands: Vec<Command>,
+}
+
+impl CommandBuffer {
+    /// Spawns a new entity on flush and passes it to `init` to add its components.
+    pub fn spawn(&mut self, label: &'static str, init: impl FnOnce(&mut Game, Entity) + 'static) {
+        self.commands.push(Command::Spawn {
+            label,
+            init: Box::new(init),
+        });
+    }
+
+    pub fn despawn(&mut self, entity: Entity) {
+        self.commands.push(Command::Despawn(entity));
+    }
+
+    pub fn add_flag(&mut self, entity: Entity, flag: Flag) {
+        self.commands.push(Command::AddFlag(entity, flag));
+    }
+
+    pub fn remove_flag(&mut self, entity: Entity, flag: Flag) {
+        self.commands.push(Command::RemoveFlag(entity, flag));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.commands.is_empty()
+    }
+}
+
+/// Declares `Actions` together with the list `Actions::all` returns, so a new action
+/// can't be forgotten in it.
+macro_rules! actions {
+    ($($action:ident,)*) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum Actions {
+            $($action,)*
+        }
+
+        impl Actions {
+            const ALL: &'static [Actions] = &[$(Actions::$action,)*];
+        }
+    };
+}
+
+actions! {
+    QuitImmediately,
+
+    MoveRight,
+    MoveLeft,
+    MoveUp,
+    MoveDown,
+
+    SelectNext,
+    FrameSelected,
+
+    DumpWorld,
+    ToggleProfiler,
+    ToggleConsole,
+    Screenshot,
+    ToggleRecording,
+
+    Dash,
+
+    Pause,
+    Confirm,
+}
+
+impl Actions {
+    pub fn all() -> &'static [Actions] {
+        Self::ALL
+    }
+
+    /// The name used in the bindings file, see `label` for the one to show players.
+    fn name(&self) -> String {
+        format!("{self:?}")
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|action| action.name() == name)
+    }
+
+    /// The name split into words, like "Move Right" for `MoveRight`.
+    pub fn label(&self) -> String {
+        let mut label = String::new();
+
+        for character in self.name().chars() {
+            if character.is_uppercase() && !label.is_empty() {
+                label.push(' ');
+            }
+
+            label.push(character);
+        }
+
+        label
+    }
+}
+
+impl std::fmt::Display for Actions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.label())
+    }
+}
+
+/// The world space render passes, drawn in the order of `Game::render_layers`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RenderPass {
+    Parallax,
+    Tilemap,
+    FixedColliders,
+    Trails,
+    Sprites,
+    Contacts,
+    Velocities,
+    CollisionGroups,
+    Broadphase,
+}
+
+impl RenderPass {
+    pub const ALL: [RenderPass; 9] = [
+        RenderPass::Parallax,
+        RenderPass::Tilemap,
+        RenderPass::FixedColliders,
+        RenderPass::Trails,
+        RenderPass::Sprites,
+        RenderPass::Contacts,
+        RenderPass::Velocities,
+        RenderPass::CollisionGroups,
+        RenderPass::Broadphase,
+    ];
+
+    // NOTE: Doubles as the profiler name
+    pub fn name(&self) -> &'static str {
+        match self {
+            RenderPass::Parallax => "render_parallax",
+            RenderPass::Tilemap => "render_tilemap",
+            RenderPass::FixedColliders => "render_fixed_colliders",
+            RenderPass::Trails => "render_trails",
+            RenderPass::Sprites => "render_sprites",
+            RenderPass::Contacts => "render_contacts",
+            RenderPass::Velocities => "render_velocities",
+            RenderPass::CollisionGroups => "render_collision_groups",
+            RenderPass::Broadphase => "render_broadphase",
+        }
+    }
+}
+
+/// What the world space passes and screen space sprites drew in the current frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RenderStats {
+    // Calls into macroquad's draw functions, before it batches them together
+    pub draw_calls: usize,
+    pub sprites_drawn: usize,
+    // Sprites skipped for lying entirely outside the view
+    pub sprites_culled: usize,
+}
+
+/// A color that only depends on `memberships`, so a collision layer looks the same
+/// every run. Colliders in every group are white and those in none gray.
+pub fn group_color(memberships: u32) -> Color {
+    // Turns between the hues of neighbouring single groups, which keeps all 32 apart
+    const GOLDEN_RATIO: f32 = 0.618_034;
+
+    let hue = match memberships {
+        0 => return GRAY,
+        u32::MAX => return WHITE,
+
+        // NOTE: Hashed hues can collide, so the groups on their own are spread out
+        _ if memberships.is_power_of_two() => {
+            (memberships.trailing_zeros() as f32 * GOLDEN_RATIO).fract()
+        }
+
+        _ => {
+            let mut hasher = Fnv1aHasher::default();
+            hasher.write_u32(memberships);
+
+            (hasher.finish() % 360) as f32 / 360.0
+        }
+    };
+
+    macroquad::color::hsl_to_rgb(hue, 0.8, 0.6)
+}
+
+/// The rect covering `aabb`, for the debug passes outlining colliders.
+#[cfg(feature = "physics")]
+fn aabb_rect(aabb: &AABB) -> Rect {
+    let (mins, extents) = (aabb.mins, aabb.extents());
+    Rect::new(mins.x, mins.y, extents.x, extents.y)
+}
+
+/// The point nearest to `point` that `camera` puts on a whole pixel of a screen
+/// of `screen_size` pixels.
+pub fn snap_to_pixel(point: Vec2, camera: &Camera2D, screen_size: Vec2) -> Vec2 {
+    let pixel = world_to_screen(camera, point, screen_size).round();
+    screen_to_world(camera, pixel, screen_size)
+}
+
+/// Like `Camera2D::world_to_screen`, for a screen of `screen_size` pixels rather than the window.
+pub fn world_to_screen(camera: &Camera2D, point: Vec2, screen_size: Vec2) -> Vec2 {
+    let clip = camera
+        .matrix()
+        .transform_point3(vec3(point.x, point.y, 0.0));
+
+    vec2(
+        (clip.x / 2.0 + 0.5) * screen_size.x,
+        (0.5 - clip.y / 2.0) * screen_size.y,
+    )
+}
+
+/// Like `Camera2D::screen_to_world`, for a screen of `screen_size` pixels rather than the window.
+pub fn screen_to_world(camera: &Camera2D, point: Vec2, screen_size: Vec2) -> Vec2 {
+    let clip = vec3(
+        point.x / screen_size.x * 2.0 - 1.0,
+        1.0 - point.y / screen_size.y * 2.0,
+        0.0,
+    );
+
+    let world = camera.matrix().inverse().transform_point3(clip);
+    vec2(world.x, world.y)
+}
+
+/// The points clicked with the editor's measure tool, in world space.
+#[cfg(feature = "editor")]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Measurement {
+    #[default]
+    None,
+    Started(Vec2),
+    Done(Vec2, Vec2),
+}
+
+#[cfg(feature = "editor")]
+impl Measurement {
+    /// The first click starts a measurement, the second ends it and the third clears it.
+    pub fn click(self, point: Vec2) -> Self {
+        match self {
+            Measurement::None => Measurement::Started(point),
+            Measurement::Started(start) => Measurement::Done(start, point),
+            Measurement::Done(..) => Measurement::None,
+        }
+    }
+}
+
+/*
+    A background texture repeated across the view. `factor` is how much of the camera's
+    movement the layer follows: `0.0` sits still in the world like everything else and
+    `1.0` sticks to the screen, so values in between read as distances in between.
+*/
+#[derive(Clone)]
+struct ParallaxLayer {
+    texture: Texture2D,
+    factor: f32,
+}
+
+impl ParallaxLayer {
+    /// Where the layer's tile (0, 0) is in the world when the camera looks at `camera_target`.
+    pub fn offset(&self, camera_target: Vec2) -> Vec2 {
+        camera_target * self.factor
+    }
+
+    /// The world positions of the top left corners of the tiles covering `view`.
+    pub fn tiles(&self, view: Rect, camera_target: Vec2) -> impl Iterator<Item = Vec2> {
+        let offset = self.offset(camera_target);
+        let size = vec2(self.texture.width(), self.texture.height()).max(Vec2::ONE);
+
+        let first = ((view.point() - offset) / size).floor();
+        let last = ((view.point() + view.size() - offset) / size).ceil();
+
+        let (xs, ys) = (first.x as i32..last.x as i32, first.y as i32..last.y as i32);
+        ys.flat_map(move |y| {
+            xs.clone()
+                .map(move |x| offset + vec2(x as f32, y as f32) * size)
+        })
+    }
+}
+
+/*
+    Starting points for `IntegrationParameters`, see `Game::with_physics_preset`. Individual
+    values can still be tuned afterwards, e.g. with `Game::set_solver_iterations`.
+
+    NOTE: `dt` is only set for completeness, `physics_system` overwrites it every step
+          with the fixed delta.
+*/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg(feature = "physics")]
+pub enum PhysicsPreset {
+    /// rapier's own defaults.
+    #[default]
+    Stable,
+
+    /// Fewer solver iterations, for scenes with many bodies that can live with some
+    /// jitter in stacks.
+    Fast,
+
+    /// More solver iterations and CCD substeps, for tall stacks and fast bodies.
+    Accurate,
+}
+
+#[cfg(feature = "physics")]
+impl PhysicsPreset {
+    pub fn integration_parameters(self) -> IntegrationParameters {
+        let defaults = IntegrationParameters {
+            dt: GOAL_DELTA_TIME as f32,
+            ..Default::default()
+        };
+
+        match self {
+            PhysicsPreset::Stable => defaults,
+
+            PhysicsPreset::Fast => IntegrationParameters {
+                max_velocity_iterations: 2,
+                max_velocity_friction_iterations: 4,
+                max_stabilization_iterations: 1,
+                max_ccd_substeps: 1,
+                ..defaults
+            },
+
+            PhysicsPreset::Accurate => IntegrationParameters {
+                max_velocity_iterations: 8,
+                max_velocity_friction_iterations: 16,
+                max_stabilization_iterations: 2,
+                max_ccd_substeps: 4,
+                ..defaults
+            },
+        }
+    }
+}
+
+pub mod constants {
+    pub const MAX_ENTITIES: usize = 1_000;
+
+    pub const SINGLE_COMPONENT: usize = 1;
+    pub const BARELY_ANY_COMPONENTS: usize = 4;
+    pub const NOT_SO_MANY_COMPONENTS: usize = 100;
+    pub const MANY_COMPONENTS: usize = MAX_ENTITIES;
+
+    pub const GOAL_DELTA_TIME: f64 = 1.0 / 60.0;
+
+    pub const MAX_TIME_SCALE: f32 = 4.0;
+
+    // Fastest a dynamic body may move after a physics step, in pixels per second
+    pub const DEFAULT_MAX_SPEED: f32 = 10_000.0;
+
+    // What `Game::rng` starts from unless seeded otherwise
+    pub const DEFAULT_SEED: u64 = 0x5eed;
+
+    // Resolution of the positions in network snapshots, in world units
+    pub const NET_POSITION_QUANTUM: f32 = 0.25;
+    // Expected seconds between two network snapshots
+    pub const NET_SNAPSHOT_INTERVAL: f64 = 0.1;
+    // How many snapshot intervals to keep extrapolating for when a snapshot is late
+    pub const NET_MAX_EXTRAPOLATION: f32 = 0.5;
+
+    // How closely a contact normal has to line up with an axis to count as ground or wall
+    pub const SURFACE_NORMAL_THRESHOLD: f32 = 0.7;
+
+    pub const BINDINGS_PATH: &str = "bindings.cfg";
+
+    // How quickly `zoom` approaches `target_zoom`, per second
+    pub const ZOOM_SMOOTHING: f32 = 8.0;
+
+    // Fraction of the view a framed entity should fill
+    pub const FRAME_FRACTION: f32 = 0.25;
+
+    // Seconds a failure like an unsaved file stays on screen for
+    pub const ERROR_TOAST_TIME: f32 = 4.0;
+}
+
+use constants::*;
+
+#[cfg(feature = "physics")]
+use crate::console::SpawnShape;
+use crate::{
+    capture::Recorder,
+    config::{Settings, SETTINGS_PATH},
+    console::{Console, ConsoleCommand},
+    menu::{Menu, MenuInput},
+    profiler::{Profiler, Timings},
+    toast::Toasts,
+    tween::Tween,
+    utils::{lerp, Fnv1aHasher, Rng, Smoothed},
+};
+
+type EntityMap = DenseSlotMap<Entity, BitSet>;
+type SparseComponentMap<T> = SparseSecondaryMap<Entity, T>;
+type DenseComponentMap<T> = SecondaryMap<Entity, T>;
+
+/// Lets code be generic over sparse and dense component containers.
+pub trait ComponentStore<T> {
+    fn get(&self, entity: Entity) -> Option<&T>;
+}
+
+impl<T> ComponentStore<T> for SparseComponentMap<T> {
+    #[inline]
+    fn get(&self, entity: Entity) -> Option<&T> {
+        SparseSecondaryMap::get(self, entity)
+    }
+}
+
+impl<T> ComponentStore<T> for DenseComponentMap<T> {
+    #[inline]
+    fn get(&self, entity: Entity) -> Option<&T> {
+        SecondaryMap::get(self, entity)
+    }
+}
+
+/// A component type together with its flag and the `Game` container holding it.
+pub trait Component: Sized + 'static {
+    const FLAG: Flag;
+    type Store: ComponentStore<Self>;
+
+    fn store(game: &Game) -> &Self::Store;
+}
+
+macro_rules! impl_components {
+    ($($(#[$meta:meta])* $component:ident => $flag:ident in $container:ident: $store:ident;)*) => {
+        $(
+            $(#[$meta])*
+            impl Component for $component {
+                const FLAG: Flag = components::$flag;
+                type Store = $store<$component>;
+
+                #[inline]
+                fn store(game: &Game) -> &Self::Store {
+                    &game.$container
+                }
+            }
+        )*
+    };
+}
+
+impl_components! {
+    TextureComponent     => TEXTURE      in texture_container:      SparseComponentMap;
+    #[cfg(feature = "physics")]
+    RigidbodyComponent   => RIGIDBODY    in rigidbody_container:    DenseComponentMap;
+    #[cfg(feature = "physics")]
+    ColliderComponent    => COLLIDER     in collider_container:     DenseComponentMap;
+    PlayerComponent      => PLAYER       in player_container:       DenseComponentMap;
+    TriggerComponent     => TRIGGER      in trigger_container:      SparseComponentMap;
+    TrailComponent       => TRAIL        in trail_container:        SparseComponentMap;
+    DisabledComponent    => DISABLED     in disabled_container:     SparseComponentMap;
+    DragZoneComponent    => DRAG_ZONE    in drag_zone_container:    SparseComponentMap;
+    TilemapComponent     => TILEMAP      in tilemap_container:      SparseComponentMap;
+    ConveyorComponent    => CONVEYOR     in conveyor_container:     SparseComponentMap;
+    NetInterpComponent   => NET_INTERP   in net_interp_container:   SparseComponentMap;
+    ScreenSpaceComponent => SCREEN_SPACE in screen_space_container: SparseComponentMap;
+    HomingComponent      => HOMING       in homing_container:       SparseComponentMap;
+    ScriptComponent      => SCRIPT       in script_container:       SparseComponentMap;
+    TimerComponent       => TIMER        in timer_container:        SparseComponentMap;
+}
+
+pub struct Game {
+    // Ecs
+    entities: EntityMap,
+
+    label_container: DenseComponentMap<&'static str>,
+
+    /*
+        Keys are reused once an entity is removed and mean nothing outside this Game,
+        so save files and network snapshots refer to entities by these ids instead.
+        They count up from 1 and are never handed out twice.
+    */
+    external_ids: DenseComponentMap<u64>,
+    entities_by_external_id: HashMap<u64, Entity>,
+    next_external_id: u64,
+
+    texture_container: SparseComponentMap<TextureComponent>,
+    #[cfg(feature = "physics")]
+    rigidbody_container: DenseComponentMap<RigidbodyComponent>,
+    #[cfg(feature = "physics")]
+    collider_container: DenseComponentMap<ColliderComponent>,
+
+    player_container: DenseComponentMap<PlayerComponent>,
+    trigger_container: SparseComponentMap<TriggerComponent>,
+    trail_container: SparseComponentMap<TrailComponent>,
+    disabled_container: SparseComponentMap<DisabledComponent>,
+    drag_zone_container: SparseComponentMap<DragZoneComponent>,
+    tilemap_container: SparseComponentMap<TilemapComponent>,
+    conveyor_container: SparseComponentMap<ConveyorComponent>,
+    net_interp_container: SparseComponentMap<NetInterpComponent>,
+    screen_space_container: SparseComponentMap<ScreenSpaceComponent>,
+    homing_container: SparseComponentMap<HomingComponent>,
+    script_container: SparseComponentMap<ScriptComponent>,
+    timer_container: SparseComponentMap<TimerComponent>,
+
+    // Entities overlapping each trigger as of the previous tick
+    trigger_overlaps: SparseComponentMap<BTreeSet<Entity>>,
+
+    events: Vec<GameEvent>,
+    stale_events: usize,
+    flag_events: bool,
+
+    commands: CommandBuffer,
+
+    /*
+        Spawns applied per logic tick, unlimited when `None`. Spawns over the budget, or
+        past `MAX_ENTITIES`, wait in `deferred_spawns` and go first on the next tick.
+    */
+    max_spawns_per_frame: Option<usize>,
+    spawns_this_frame: usize,
+    deferred_spawns: VecDeque<Command>,
+
+    // Queued despawns, applied by `flush_despawns` at the end of the tick
+    pending_despawns: Vec<Entity>,
+
+    // Advanced at the start of every logic tick and dropped once finished
+    tweens: Vec<Tween<Game>>,
+
+    // The one source of randomness, so seeded simulations replay exactly
+    rng: Rng,
+
+    // Seconds of simulation so far, the clock network snapshots are timed against
+    net_time: f64,
+
+    // Other
+    world_bounds: Option<Rect>,
+    kill_plane_y: Option<f32>,
+    // No speed limit when `None`, non finite velocities are reset either way
+    max_speed: Option<f32>,
+
+    // Acceleration applied to every dynamic body, in the same units as `gravity`
+    wind: Vec2,
+    // How strongly the wind affects a body, `1.0` when missing
+    wind_drag: SparseComponentMap<f32>,
+    time_scale: f32,
+
+    #[cfg(feature = "physics")]
+    collider_scales: SparseComponentMap<ColliderScale>,
+    // Groups the colliders turned off by `set_collider_enabled` go back to, like `DisabledComponent`
+    #[cfg(feature = "physics")]
+    disabled_colliders: SparseComponentMap<Vec<(InteractionGroups, InteractionGroups)>>,
+
+    resources: Resources,
+
+    settings: Settings,
+    profiler: Profiler,
+    console: Console,
+    recorder: Recorder,
+    toasts: Toasts,
+
+    // Used for entities spawned at runtime, e.g. from the console
+    spawn_texture: Option<Texture2D>,
+    #[cfg(feature = "physics")]
+    default_material: Material,
+
+    // How far rendering is between the last two fixed steps, in `0.0..=1.0`
+    render_alpha: f32,
+    // Draws sprites at whole screen pixels so that pixel art doesn't shimmer
+    pixel_snap: bool,
+    // NOTE: A `Cell` since the rendering systems only get `&self`
+    render_stats: Cell<RenderStats>,
+
+    // Editor
+    #[cfg(feature = "editor")]
+    selected: Option<Entity>,
+    #[cfg(feature = "editor")]
+    egui_wants_keyboard: bool,
+    #[cfg(feature = "editor")]
+    egui_wants_pointer: bool,
+    #[cfg(feature = "editor")]
+    rebinding: Option<Actions>,
+    // Clicks in the world measure distances instead of doing anything else while set
+    #[cfg(feature = "editor")]
+    measure_tool: bool,
+    #[cfg(feature = "editor")]
+    measurement: Measurement,
+
+    zoom: f32,
+    target_zoom: f32,
+    camera: Camera2D,
+    // Followed by `camera_follow_system`, the first player when `None`
+    camera_target_entity: Option<Entity>,
+
+    // Passes missing from the list are disabled
+    render_layers: Vec<RenderPass>,
+    background_color: Color,
+    // Drawn back to front by `RenderPass::Parallax`
+    parallax_layers: Vec<ParallaxLayer>,
+
+    keys: HashMap<Actions, KeyCode>,
+    // Gathered over the frame for the next fixed step, see `player_input_system`
+    #[cfg(feature = "physics")]
+    player_input: PlayerInput,
+
+    player_tuning: PlayerTuning,
+    camera_tuning: CameraTuning,
+
+    // Physics
+    #[cfg(feature = "physics")]
+    gravity: nalgebra::Vector2<f32>,
+    #[cfg(feature = "physics")]
+    rigid_body_set: RigidBodySet,
+    #[cfg(feature = "physics")]
+    collider_set: ColliderSet,
+    #[cfg(feature = "physics")]
+    integration_parameters: IntegrationParameters,
+    #[cfg(feature = "physics")]
+    physics_pipeline: PhysicsPipeline,
+    #[cfg(feature = "physics")]
+    island_manager: IslandManager,
+    #[cfg(feature = "physics")]
+    broad_phase: BroadPhase,
+    #[cfg(feature = "physics")]
+    narrow_phase: NarrowPhase,
+    #[cfg(feature = "physics")]
+    impulse_joint_set: ImpulseJointSet,
+    #[cfg(feature = "physics")]
+    multibody_joint_set: MultibodyJointSet,
+    #[cfg(feature = "physics")]
+    ccd_solver: CCDSolver,
+    #[cfg(feature = "physics")]
+    physics_hooks: (),
+    #[cfg(feature = "physics")]
+    event_handler: (),
+}
+
+impl Default for Game {
+    fn default() -> Self {
+        const ZOOM: f32 = -0.002;
+
+        Self {
+            // Ecs
+            entities: EntityMap::with_capacity_and_key(MAX_ENTITIES),
+
+            label_container: DenseComponentMap::with_capacity(MANY_COMPONENTS),
+
+            external_ids: DenseComponentMap::with_capacity(MANY_COMPONENTS),
+            entities_by_external_id: HashMap::with_capacity(MANY_COMPONENTS),
+            next_external_id: 1,
+
+            texture_container: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+            #[cfg(feature = "physics")]
+            rigidbody_container: DenseComponentMap::with_capacity(MANY_COMPONENTS),
+            #[cfg(feature = "physics")]
+            collider_container: DenseComponentMap::with_capacity(MANY_COMPONENTS),
+
+            player_container: DenseComponentMap::with_capacity(SINGLE_COMPONENT),
+            trigger_container: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+            trail_container: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+            disabled_container: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+            drag_zone_container: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+            tilemap_container: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+            conveyor_container: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+            net_interp_container: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+            screen_space_container: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+            homing_container: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+            script_container: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+            timer_container: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+
+            trigger_overlaps: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+
+            events: Vec::with_capacity(NOT_SO_MANY_COMPONENTS),
+            stale_events: 0,
+            flag_events: false,
+
+            commands: CommandBuffer::default(),
+
+            max_spawns_per_frame: None,
+            spawns_this_frame: 0,
+            deferred_spawns: VecDeque::new(),
+
+            pending_despawns: Vec::new(),
+
+            tweens: Vec::new(),
+
+            rng: Rng::new(DEFAULT_SEED),
+
+            net_time: 0.0,
+
+            // Other
+            world_bounds: None,
+            kill_plane_y: None,
+            max_speed: Some(DEFAULT_MAX_SPEED),
+
+            wind: Vec2::ZERO,
+            wind_drag: SparseComponentMap::with_capacity(NOT_SO_MANY_COMPONENTS),
+            time_scale: 1.0,
+
+            #[cfg(feature = "physics")]
+            collider_scales: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+            #[cfg(feature = "physics")]
+            disabled_colliders: SparseComponentMap::with_capacity(BARELY_ANY_COMPONENTS),
+
+            resources: Resources::default(),
+
+            settings: Settings::default(),
+            profiler: Profiler::default(),
+            console: Console::default(),
+            recorder: Recorder::default(),
+            toasts: Toasts::default(),
+
+            spawn_texture: None,
+            #[cfg(feature = "physics")]
+            default_material: Material::default(),
+
+            render_alpha: 1.0,
+            pixel_snap: false,
+            render_stats: Cell::default(),
+
+            #[cfg(feature = "editor")]
+            selected: None,
+            #[cfg(feature = "editor")]
+            egui_wants_keyboard: false,
+            #[cfg(feature = "editor")]
+            egui_wants_pointer: false,
+            #[cfg(feature = "editor")]
+            rebinding: None,
+            #[cfg(feature = "editor")]
+            measure_tool: false,
+            #[cfg(feature = "editor")]
+            measurement: Measurement::None,
+
+            zoom: ZOOM,
+            target_zoom: ZOOM,
+            camera: Camera2D {
+                // NOTE: Rendering applies the aspect ratio, so a Game works without a window
+                zoom: vec2(ZOOM, ZOOM),
+
+                target: vec2(500.0, 500.0),
+                offset: vec2(0.0, 0.0),
+
+                ..Default::default()
+            },
+            camera_target_entity: None,
+
+            render_layers: vec![
+                RenderPass::Parallax,
+                RenderPass::Tilemap,
+                RenderPass::FixedColliders,
+                RenderPass::Trails,
+                RenderPass::Sprites,
+            ],
+            background_color: BLACK,
+            parallax_layers: vec![],
+
+            keys: HashMap::from([
+                (Actions::QuitImmediately, KeyCode::Escape),
+                (Actions::MoveRight, KeyCode::D),
+                (Actions::MoveLeft, KeyCode::A),
+                (Actions::MoveUp, KeyCode::W),
+                (Actions::MoveDown, KeyCode::S),
+                (Actions::SelectNext, KeyCode::Tab),
+                (Actions::FrameSelected, KeyCode::F),
+                (Actions::DumpWorld, KeyCode::F1),
+                (Actions::ToggleProfiler, KeyCode::F3),
+                (Actions::ToggleConsole, KeyCode::GraveAccent),
+                (Actions::Screenshot, KeyCode::F12),
+                (Actions::ToggleRecording, KeyCode::F10),
+                (Actions::Dash, KeyCode::Space),
+                (Actions::Pause, KeyCode::P),
+                (Actions::Confirm, KeyCode::Enter),
+            ]),
+            #[cfg(feature = "physics")]
+            player_input: PlayerInput::default(),
+
+            player_tuning: PlayerTuning::default(),
+            camera_tuning: CameraTuning::default(),
+
+            // Physics
+            #[cfg(feature = "physics")]
+            gravity: vector![0.0, 569.1337],
+
+            #[cfg(feature = "physics")]
+            rigid_body_set: RigidBodySet::new(),
+            #[cfg(feature = "physics")]
+            collider_set: ColliderSet::new(),
+            #[cfg(feature = "physics")]
+            integration_parameters: IntegrationParameters::default(),
+            #[cfg(feature = "physics")]
+            physics_pipeline: PhysicsPipeline::new(),
+            #[cfg(feature = "physics")]
+            island_manager: IslandManager::new(),
+            #[cfg(feature = "physics")]
+            broad_phase: BroadPhase::new(),
+            #[cfg(feature = "physics")]
+            narrow_phase: NarrowPhase::new(),
+            #[cfg(feature = "physics")]
+            impulse_joint_set: ImpulseJointSet::new(),
+            #[cfg(feature = "physics")]
+            multibody_joint_set: MultibodyJointSet::new(),
+            #[cfg(feature = "physics")]
+            ccd_solver: CCDSolver::new(),
+            #[cfg(feature = "physics")]
+            physics_hooks: (),
+            #[cfg(feature = "physics")]
+            event_handler: (),
+        }
+    }
+}
+
+impl Game {
+    #[allow(dead_code)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn with_physics_preset(preset: PhysicsPreset) -> Self {
+        let mut result = Self::default();
+        result.apply_physics_preset(preset);
+        result
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        let mut result = Self::default();
+        result.seed_rng(seed);
+        result
+    }
+
+    /*
+        An independent copy of the world, e.g. for split screen or an editor preview.
+
+        NOTE: The rapier sets are cloned whole, so every body and collider keeps its
+              handle and the copied components point at the copies without remapping.
+              Queued spawns and tweens hold closures and are left behind, as are the
+              console, profiler and recorder.
+    */
+    pub fn clone_world(&self) -> Self {
+        Self {
+            // Ecs
+            entities: self.entities.clone(),
+
+            label_container: self.label_container.clone(),
+
+            external_ids: self.external_ids.clone(),
+            entities_by_external_id: self.entities_by_external_id.clone(),
+            next_external_id: self.next_external_id,
+
+            texture_container: self.texture_container.clone(),
+            #[cfg(feature = "physics")]
+            rigidbody_container: self.rigidbody_container.clone(),
+            #[cfg(feature = "physics")]
+            collider_container: self.collider_container.clone(),
+
+            player_container: self.player_container.clone(),
+            trigger_container: self.trigger_container.clone(),
+            trail_container: self.trail_container.clone(),
+            disabled_container: self.disabled_container.clone(),
+            drag_zone_container: self.drag_zone_container.clone(),
+            tilemap_container: self.tilemap_container.clone(),
+            conveyor_container: self.conveyor_container.clone(),
+            net_interp_container: self.net_interp_container.clone(),
+            screen_space_container: self.screen_space_container.clone(),
+            homing_container: self.homing_container.clone(),
+            script_container: self.script_container.clone(),
+            timer_container: self.timer_container.clone(),
+
+            trigger_overlaps: self.trigger_overlaps.clone(),
+
+            events: self.events.clone(),
+            stale_events: self.stale_events,
+            flag_events: self.flag_events,
+
+            commands: CommandBuffer::default(),
+
+            max_spawns_per_frame: self.max_spawns_per_frame,
+            spawns_this_frame: self.spawns_this_frame,
+            deferred_spawns: VecDeque::new(),
+
+            pending_despawns: self.pending_despawns.clone(),
+
+            tweens: Vec::new(),
+
+            rng: self.rng,
+
+            net_time: self.net_time,
+
+            // Other
+            world_bounds: self.world_bounds,
+            kill_plane_y: self.kill_plane_y,
+            max_speed: self.max_speed,
+
+            wind: self.wind,
+            wind_drag: self.wind_drag.clone(),
+            time_scale: self.time_scale,
+
+            #[cfg(feature = "physics")]
+            collider_scales: self.collider_scales.clone(),
+            #[cfg(feature = "physics")]
+            disabled_colliders: self.disabled_colliders.clone(),
+
+            resources: self.resources,
+
+            settings: self.settings.clone(),
+            profiler: Profiler::default(),
+            console: Console::default(),
+            recorder: Recorder::default(),
+            toasts: Toasts::default(),
+
+            spawn_texture: self.spawn_texture,
+            #[cfg(feature = "physics")]
+            default_material: self.default_material,
+
+            render_alpha: self.render_alpha,
+            pixel_snap: self.pixel_snap,
+            render_stats: Cell::default(),
+
+            #[cfg(feature = "editor")]
+            selected: None,
+            #[cfg(feature = "editor")]
+            egui_wants_keyboard: false,
+            #[cfg(feature = "editor")]
+            egui_wants_pointer: false,
+            #[cfg(feature = "editor")]
+            rebinding: None,
+            #[cfg(feature = "editor")]
+            measure_tool: false,
+            #[cfg(feature = "editor")]
+            measurement: Measurement::None,
+
+            zoom: self.zoom,
+            target_zoom: self.target_zoom,
+            camera: self.camera,
+            camera_target_entity: self.camera_target_entity,
+
+            render_layers: self.render_layers.clone(),
+            background_color: self.background_color,
+            parallax_layers: self.parallax_layers.clone(),
+
+            keys: self.keys.clone(),
+            #[cfg(feature = "physics")]
+            player_input: PlayerInput::default(),
+
+            player_tuning: self.player_tuning,
+            camera_tuning: self.camera_tuning,
+
+            // Physics
+            #[cfg(feature = "physics")]
+            gravity: self.gravity,
+
+            #[cfg(feature = "physics")]
+            rigid_body_set: self.rigid_body_set.clone(),
+            #[cfg(feature = "physics")]
+            collider_set: self.collider_set.clone(),
+            #[cfg(feature = "physics")]
+            integration_parameters: self.integration_parameters,
+            #[cfg(feature = "physics")]
+            physics_pipeline: PhysicsPipeline::new(),
+            #[cfg(feature = "physics")]
+            island_manager: self.island_manager.clone(),
+            #[cfg(feature = "physics")]
+            broad_phase: self.broad_phase.clone(),
+            #[cfg(feature = "physics")]
+            narrow_phase: self.narrow_phase.clone(),
+            #[cfg(feature = "physics")]
+            impulse_joint_set: self.impulse_joint_set.clone(),
+            #[cfg(feature = "physics")]
+            multibody_joint_set: self.multibody_joint_set.clone(),
+            #[cfg(feature = "physics")]
+            ccd_solver: self.ccd_solver.clone(),
+            #[cfg(feature = "physics")]
+            physics_hooks: (),
+            #[cfg(feature = "physics")]
+            event_handler: (),
+        }
+    }
+}
+
+// Ecs Api
+impl Game {
+    pub fn new_entity(&mut self, label: &'static str) -> Entity {
+        let entity = self.entities.insert(BitSet::empty());
+
+        self.label_container.insert(entity, label);
+
+        let external_id = self.next_external_id;
+        self.next_external_id += 1;
+        self.external_ids.insert(entity, external_id);
+        self.entities_by_external_id.insert(external_id, entity);
+
+        entity
+    }
+
+    pub fn external_id_of(&self, entity: Entity) -> Option<u64> {
+        self.external_ids.get(entity).copied()
+    }
+
+    pub fn entity_by_external_id(&self, external_id: u64) -> Option<Entity> {
+        self.entities_by_external_id.get(&external_id).copied()
+    }
+
+    /// Removes the entity along with all of its components, including
+    /// its rigidbody and colliders.
+    pub fn remove_entity(&mut self, entity: Entity) {
+        if self.entities.remove(entity).is_none() {
+            return;
+        }
+
+        self.label_container.remove(entity);
+        if let Some(external_id) = self.external_ids.remove(entity) {
+            self.entities_by_external_id.remove(&external_id);
+        }
+        self.texture_container.remove(entity);
+        #[cfg(feature = "physics")]
+        self.collider_container.remove(entity);
+        self.player_container.remove(entity);
+        self.trigger_container.remove(entity);
+        self.trigger_overlaps.remove(entity);
+        self.trail_container.remove(entity);
+        self.disabled_container.remove(entity);
+        self.drag_zone_container.remove(entity);
+        self.conveyor_container.remove(entity);
+        self.net_interp_container.remove(entity);
+        self.screen_space_container.remove(entity);
+        self.homing_container.remove(entity);
+        self.script_container.remove(entity);
+        self.timer_container.remove(entity);
+        self.wind_drag.remove(entity);
+        #[cfg(feature = "physics")]
+        self.collider_scales.remove(entity);
+        #[cfg(feature = "physics")]
+        self.disabled_colliders.remove(entity);
+
+        #[cfg(not(feature = "physics"))]
+        self.tilemap_container.remove(entity);
+
+        #[cfg(feature = "physics")]
+        if let Some(tilemap) = self.tilemap_container.remove(entity) {
+            for handle in tilemap.collider_handles {
+                self.collider_set.remove(
+                    handle,
+                    &mut self.island_manager,
+                    &mut self.rigid_body_set,
+                    true,
+                );
+            }
+        }
+
+        #[cfg(feature = "physics")]
+        if let Some(rigidbody_component) = self.rigidbody_container.remove(entity) {
+            // NOTE: Also removes every collider attached to the rigidbody
+            self.rigid_body_set.remove(
+                rigidbody_component.rigidbody_handle,
+                &mut self.island_manager,
+                &mut self.collider_set,
+                &mut self.impulse_joint_set,
+                &mut self.multibody_joint_set,
+                true,
+            );
+        }
+    }
+
+    pub fn add_flag(&mut self, entity: Entity, flag: Flag) {
+        let alive = self.try_add_flag(entity, flag);
+        assert!(alive, "add_flag on the removed entity {entity:?}");
+    }
+
+    pub fn remove_flag(&mut self, entity: Entity, flag: Flag) {
+        let alive = self.try_remove_flag(entity, flag);
+        assert!(alive, "remove_flag on the removed entity {entity:?}");
+    }
+
+    /// Like `add_flag`, but returns `false` instead of panicking for a removed entity.
+    pub fn try_add_flag(&mut self, entity: Entity, flag: Flag) -> bool {
+        self.change_flags(entity, |bitset| bitset.include_flag(flag))
+    }
+
+    /// Like `remove_flag`, but returns `false` instead of panicking for a removed entity.
+    pub fn try_remove_flag(&mut self, entity: Entity, flag: Flag) -> bool {
+        self.change_flags(entity, |bitset| bitset.exclude_flag(flag))
+    }
+
+    /// Every flag change goes through here, so this is the one place to observe them.
+    #[inline]
+    fn change_flags(&mut self, entity: Entity, change: impl FnOnce(&mut BitSet)) -> bool {
+        let Some(bitset) = self.entities.get_mut(entity) else {
+            return false;
+        };
+
+        let old = *bitset;
+        change(bitset);
+
+        if self.flag_events && *bitset != old {
+            let new = *bitset;
+            self.events
+                .push(GameEvent::FlagsChanged { entity, old, new });
+        }
+
+        true
+    }
+
+    /// Whether `entity` still refers to a live entity. Keys are generational, so a
+    /// handle to a removed entity stays dead even after its slot is reused.
+    pub fn is_alive(&self, entity: Entity) -> bool {
+        self.entities.contains_key(entity)
+    }
+
+    pub fn has_flag(&self, entity: Entity, flag: Flag) -> bool {
+        self.entities
+            .get(entity)
+            .is_some_and(|bitset| bitset.contains(flag))
+    }
+
+    #[inline]
+    pub fn add_texture(&mut self, entity: Entity, component: TextureComponent) {
+        self.texture_container.insert(entity, component);
+        self.add_flag(entity, components::TEXTURE);
+    }
+
+    /// Swaps the sprite of `entity`, keeping its size and color. Entities without
+    /// a `TextureComponent` are left alone, like for the other texture setters.
+    pub fn set_texture(&mut self, entity: Entity, texture: Texture2D) {
+        if let Some(component) = self.texture_container.get_mut(entity) {
+            component.texture = texture;
+        }
+    }
+
+    pub fn set_texture_color(&mut self, entity: Entity, color: Color) {
+        if let Some(component) = self.texture_container.get_mut(entity) {
+            component.color = color;
+        }
+    }
+
+    pub fn set_texture_size(&mut self, entity: Entity, size: Vec2) {
+        if let Some(component) = self.texture_container.get_mut(entity) {
+            component.size = size;
+        }
+    }
+
+    #[inline]
+    /// The respawn point starts out at the player's current position if it
+    /// already has a rigidbody.
+    #[cfg(feature = "physics")]
+    pub fn add_player_component(&mut self, entity: Entity, mut component: PlayerComponent) {
+        if let Some(position) = self.position(entity) {
+            component.respawn_point = position;
+        }
+
+        self.player_container.insert(entity, component);
+        self.add_flag(entity, components::PLAYER);
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn add_physics(&mut self, entity: Entity, rigid_body: RigidBody, mut collider: Collider) {
+        // NOTE: Lets us find our way back from a collider handle to its entity
+        collider.user_data = entity.data().as_ffi() as u128;
+
+        let mut locked_axes = LockedAxes::empty();
+        locked_axes.set(LockedAxes::ROTATION_LOCKED, rigid_body.is_rotation_locked());
+        locked_axes.set(
+            LockedAxes::TRANSLATION_LOCKED,
+            rigid_body.is_translation_locked(),
+        );
+
+        let previous_position = *rigid_body.position();
+        let rigidbody_handle = self.rigid_body_set.insert(rigid_body);
+
+        let collider_handle = self.collider_set.insert_with_parent(
+            collider,
+            rigidbody_handle,
+            &mut self.rigid_body_set,
+        );
+
+        self.rigidbody_container.insert(
+            entity,
+            RigidbodyComponent {
+                rigidbody_handle,
+                locked_axes,
+                previous_position,
+            },
+        );
+
+        self.collider_container
+            .insert(entity, ColliderComponent::new(collider_handle));
+
+        self.add_flag(entity, components::RIGIDBODY);
+        self.add_flag(entity, components::COLLIDER);
+    }
+
+    /// A fixed collider covering the convex hull of `points`, in world space.
+    #[cfg(feature = "physics")]
+    pub fn add_convex_collider(
+        &mut self,
+        entity: Entity,
+        points: &[Vec2],
+    ) -> Result<(), ShapeError> {
+        let collider = convex_collider(points)?.build();
+        self.add_fixed_collider(entity, collider);
+
+        Ok(())
+    }
+
+    /// A fixed chain of segments through `points`, in world space, e.g. for sloped ground.
+    #[cfg(feature = "physics")]
+    pub fn add_polyline_collider(
+        &mut self,
+        entity: Entity,
+        points: &[Vec2],
+    ) -> Result<(), ShapeError> {
+        if points.len() < 2 {
+            return Err(ShapeError::TooFewPoints {
+                got: points.len(),
+                needed: 2,
+            });
+        }
+
+        let points = points
+            .iter()
+            .map(|point| point![point.x, point.y])
+            .collect::<Vec<_>>();
+
+        self.add_fixed_collider(entity, ColliderBuilder::polyline(points, None).build());
+
+        Ok(())
+    }
+
+    /*
+        Fixed terrain with `heights` evenly spread over `width`, centered on `position`.
+        Heights are multiplied by `height_scale` and point up, towards negative y, so
+        bigger values are higher up on screen.
+    */
+    #[cfg(feature = "physics")]
+    pub fn add_heightfield_collider(
+        &mut self,
+        entity: Entity,
+        position: Vec2,
+        heights: &[f32],
+        width: f32,
+        height_scale: f32,
+    ) -> Result<(), ShapeError> {
+        if heights.len() < 2 {
+            return Err(ShapeError::TooFewPoints {
+                got: heights.len(),
+                needed: 2,
+            });
+        }
+
+        let heights =
+            nalgebra::DVector::from_iterator(heights.len(), heights.iter().map(|height| -height));
+        let collider = ColliderBuilder::heightfield(heights, vector![width, height_scale])
+            .translation(vector![position.x, position.y])
+            .build();
+
+        self.add_fixed_collider(entity, collider);
+
+        Ok(())
+    }
+
+    /*
+        Attaches another collider to the entity's rigidbody, `local_offset` away from the
+        body, e.g. for an L-shaped platform or a foot sensor. Returns `None` when the
+        entity has no rigidbody and collider to add to yet.
+    */
+    #[cfg(feature = "physics")]
+    pub fn add_extra_collider(
+        &mut self,
+        entity: Entity,
+        mut collider: Collider,
+        local_offset: Vec2,
+    ) -> Option<ColliderHandle> {
+        let rigidbody_handle = self.rigidbody_container.get(entity)?.rigidbody_handle;
+        let component = self.collider_container.get_mut(entity)?;
+
+        collider.user_data = entity.data().as_ffi() as u128;
+        // NOTE: Before insertion, the position is taken as the offset from the parent
+        collider.set_translation(vector![local_offset.x, local_offset.y]);
+
+        let collider_handle = self.collider_set.insert_with_parent(
+            collider,
+            rigidbody_handle,
+            &mut self.rigid_body_set,
+        );
+
+        component.collider_handles.push(collider_handle);
+
+        // NOTE: A disabled entity's new collider has to stay out of the simulation too
+        let collider = &mut self.collider_set[collider_handle];
+        let mut groups = (collider.collision_groups(), collider.solver_groups());
+        let mut excluded = false;
+
+        if let Some(saved) = self.disabled_colliders.get_mut(entity) {
+            saved.push(groups);
+            groups = (InteractionGroups::none(), InteractionGroups::none());
+            excluded = true;
+        }
+
+        if let Some(state) = self.disabled_container.get_mut(entity) {
+            state.collider_groups.push(groups);
+            excluded = true;
+        }
+
+        if excluded {
+            collider.set_collision_groups(InteractionGroups::none());
+            collider.set_solver_groups(InteractionGroups::none());
+        }
+
+        Some(collider_handle)
+    }
+
+    /// Attaches a `half_width` by `half_height` sensor centered on the bottom edge of a
+    /// player's main collider, which then decides whether the player is grounded.
+    #[cfg(feature = "physics")]
+    pub fn add_feet_sensor(
+        &mut self,
+        entity: Entity,
+        half_width: f32,
+        half_height: f32,
+    ) -> Option<ColliderHandle> {
+        if !self.player_container.contains_key(entity) {
+            return None;
+        }
+
+        let main = &self.collider_set[*self.colliders_of(entity).first()?];
+        let bottom = main
+            .position_wrt_parent()
+            .map_or(0.0, |isometry| isometry.translation.y)
+            + main.shape().compute_local_aabb().maxs.y;
+
+        let sensor = ColliderBuilder::cuboid(half_width, half_height)
+            .sensor(true)
+            // NOTE: Mustn't change the player's mass
+            .density(0.0)
+            .build();
+
+        let handle = self.add_extra_collider(entity, sensor, vec2(0.0, bottom))?;
+
+        if let Some(player) = self.player_container.get_mut(entity) {
+            player.feet_sensor = Some(handle);
+        }
+
+        Some(handle)
+    }
+
+    #[inline]
+    #[cfg(feature = "physics")]
+    pub fn add_fixed_collider(&mut self, entity: Entity, collider: Collider) {
+        let rigid_body = RigidBodyBuilder::fixed().build();
+        self.add_physics(entity, rigid_body, collider);
+
+        self.add_flag(entity, components::FIXED_COLLIDER);
+    }
+
+    #[cfg(feature = "physics")]
+    fn add_fixed_sensor(&mut self, entity: Entity, mut collider: Collider) {
+        collider.set_sensor(true);
+
+        let rigid_body = RigidBodyBuilder::fixed().build();
+        self.add_physics(entity, rigid_body, collider);
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn add_drag_zone(
+        &mut self,
+        entity: Entity,
+        component: DragZoneComponent,
+        collider: Collider,
+    ) {
+        self.add_fixed_sensor(entity, collider);
+
+        self.drag_zone_container.insert(entity, component);
+        self.add_flag(entity, components::DRAG_ZONE);
+    }
+
+    /// Makes the entity's rigidbody kinematic and lets network snapshots drive it.
+    #[cfg(feature = "physics")]
+    pub fn add_net_interp(&mut self, entity: Entity) {
+        let Some(rigidbody) = self.rigidbody_mut(entity) else {
+            return;
+        };
+
+        rigidbody.set_body_type(RigidBodyType::KinematicPositionBased);
+
+        let isometry = rigidbody.position();
+        let position = vec2(isometry.translation.x, isometry.translation.y);
+        let rotation = isometry.rotation.angle();
+
+        let component = NetInterpComponent::new(position, rotation, self.net_time);
+        self.net_interp_container.insert(entity, component);
+        self.add_flag(entity, components::NET_INTERP);
+    }
+
+    pub fn add_screen_space(&mut self, entity: Entity, component: ScreenSpaceComponent) {
+        self.screen_space_container.insert(entity, component);
+        self.add_flag(entity, components::SCREEN_SPACE);
+    }
+
+    pub fn add_homing(&mut self, entity: Entity, component: HomingComponent) {
+        self.homing_container.insert(entity, component);
+        self.add_flag(entity, components::HOMING);
+    }
+
+    pub fn add_script(&mut self, entity: Entity, component: ScriptComponent) {
+        self.script_container.insert(entity, component);
+        self.add_flag(entity, components::SCRIPT);
+    }
+
+    pub fn add_timer(&mut self, entity: Entity, component: TimerComponent) {
+        self.timer_container.insert(entity, component);
+        self.add_flag(entity, components::TIMER);
+    }
+
+    /// Starts the timer `name` of `entity` over, adding a `TimerComponent` when it has none.
+    pub fn set_timer(&mut self, entity: Entity, name: &'static str, duration: f32) {
+        if !self.is_alive(entity) {
+            return;
+        }
+
+        if !self.timer_container.contains_key(entity) {
+            self.add_timer(entity, TimerComponent::default());
+        }
+
+        if let Some(timers) = self.timer_container.get_mut(entity) {
+            timers.set(name, duration);
+        }
+    }
+
+    pub fn reset_timer(&mut self, entity: Entity, name: &'static str) {
+        if let Some(timers) = self.timer_container.get_mut(entity) {
+            timers.reset(name);
+        }
+    }
+
+    pub fn timer_expired(&self, entity: Entity, name: &str) -> bool {
+        self.timer_container
+            .get(entity)
+            .is_some_and(|timers| timers.is_expired(name))
+    }
+
+    pub fn timer_running(&self, entity: Entity, name: &str) -> bool {
+        self.timer_container
+            .get(entity)
+            .is_some_and(|timers| timers.is_running(name))
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn add_conveyor(
+        &mut self,
+        entity: Entity,
+        component: ConveyorComponent,
+        collider: Collider,
+    ) {
+        self.add_fixed_collider(entity, collider);
+
+        self.conveyor_container.insert(entity, component);
+        self.add_flag(entity, components::CONVEYOR);
+    }
+
+    /// Attaches `collider` as a fixed sensor. Entities entering or leaving it
+    /// produce `GameEvent::TriggerEnter` / `GameEvent::TriggerExit`.
+    #[cfg(feature = "physics")]
+    pub fn add_trigger(&mut self, entity: Entity, component: TriggerComponent, collider: Collider) {
+        self.add_fixed_sensor(entity, collider);
+
+        self.trigger_container.insert(entity, component);
+        self.trigger_overlaps.insert(entity, BTreeSet::new());
+        self.add_flag(entity, components::TRIGGER);
+    }
+
+    /*
+        NOTE: Disabled entities keep all of their components but are skipped by the
+              systems. Their rigidbody is frozen in place as a fixed body with its
+              colliders excluded from every interaction, and restored when re-enabled.
+    */
+    #[cfg(feature = "physics")]
+    pub fn set_enabled(&mut self, entity: Entity, enabled: bool) {
+        if !self.is_alive(entity) || self.is_enabled(entity) == enabled {
+            return;
+        }
+
+        let rigidbody_handle = self
+            .rigidbody_container
+            .get(entity)
+            .map(|component| component.rigidbody_handle);
+        let collider_handles = self
+            .collider_container
+            .get(entity)
+            .map(|component| component.collider_handles.clone())
+            .unwrap_or_default();
+
+        if enabled {
+            self.remove_flag(entity, components::DISABLED);
+
+            let state = match self.disabled_container.remove(entity) {
+                Some(state) => state,
+                None => return,
+            };
+
+            if let Some(rigidbody) = rigidbody_handle.and_then(|h| self.rigid_body_set.get_mut(h)) {
+                rigidbody.set_body_type(state.body_type);
+                rigidbody.set_linvel(state.linvel, true);
+                rigidbody.set_angvel(state.angvel, true);
+            }
+
+            for (handle, (collision_groups, solver_groups)) in
+                collider_handles.into_iter().zip(state.collider_groups)
+            {
+                if let Some(collider) = self.collider_set.get_mut(handle) {
+                    collider.set_collision_groups(collision_groups);
+                    collider.set_solver_groups(solver_groups);
+                }
+            }
+        } else {
+            self.add_flag(entity, components::DISABLED);
+
+            let (body_type, linvel, angvel) =
+                match rigidbody_handle.and_then(|h| self.rigid_body_set.get_mut(h)) {
+                    Some(rigidbody) => {
+                        let state = (
+                            rigidbody.body_type(),
+                            *rigidbody.linvel(),
+                            rigidbody.angvel(),
+                        );
+
+                        rigidbody.set_body_type(RigidBodyType::Fixed);
+                        rigidbody.set_linvel(vector![0.0, 0.0], false);
+                        rigidbody.set_angvel(0.0, false);
+
+                        state
+                    }
+                    None => (RigidBodyType::Fixed, vector![0.0, 0.0], 0.0),
+                };
+
+            let collider_groups = collider_handles
+                .into_iter()
+                .map(|handle| match self.collider_set.get_mut(handle) {
+                    Some(collider) => {
+                        let state = (collider.collision_groups(), collider.solver_groups());
+
+                        collider.set_collision_groups(InteractionGroups::none());
+                        collider.set_solver_groups(InteractionGroups::none());
+
+                        state
+                    }
+                    None => (InteractionGroups::all(), InteractionGroups::all()),
+                })
+                .collect();
+
+            self.disabled_container.insert(
+                entity,
+                DisabledComponent {
+                    body_type,
+                    linvel,
+                    angvel,
+                    collider_groups,
+                },
+            );
+        }
+    }
+
+    /*
+        Turns the colliders of `entity` non solid, or solid again, without touching the
+        rest of its physics. Gravity and velocity still apply, so it falls through the
+        floor unless something else holds it up.
+
+        NOTE: Like a disabled entity's, the colliders are excluded from every interaction,
+              triggers and contacts included. Each of the two only restores the groups
+              once the other isn't holding the colliders off as well.
+    */
+    #[cfg(feature = "physics")]
+    pub fn set_collider_enabled(&mut self, entity: Entity, enabled: bool) {
+        if !self.is_alive(entity) || self.is_collider_enabled(entity) == enabled {
+            return;
+        }
+
+        let collider_handles = self
+            .collider_container
+            .get(entity)
+            .map(|component| component.collider_handles.clone())
+            .unwrap_or_default();
+
+        let none = (InteractionGroups::none(), InteractionGroups::none());
+
+        if enabled {
+            let Some(groups) = self.disabled_colliders.remove(entity) else {
+                return;
+            };
+
+            // NOTE: Still frozen, so only hand the groups over to `set_enabled`
+            if let Some(state) = self.disabled_container.get_mut(entity) {
+                state.collider_groups = groups;
+                return;
+            }
+
+            for (handle, (collision_groups, solver_groups)) in
+                collider_handles.into_iter().zip(groups)
+            {
+                if let Some(collider) = self.collider_set.get_mut(handle) {
+                    collider.set_collision_groups(collision_groups);
+                    collider.set_solver_groups(solver_groups);
+                }
+            }
+        } else {
+            let groups = match self.disabled_container.get_mut(entity) {
+                Some(state) => std::mem::replace(
+                    &mut state.collider_groups,
+                    vec![none; collider_handles.len()],
+                ),
+
+                None => collider_handles
+                    .into_iter()
+                    .map(|handle| match self.collider_set.get_mut(handle) {
+                        Some(collider) => {
+                            let state = (collider.collision_groups(), collider.solver_groups());
+
+                            collider.set_collision_groups(none.0);
+                            collider.set_solver_groups(none.1);
+
+                            state
+                        }
+                        None => (InteractionGroups::all(), InteractionGroups::all()),
+                    })
+                    .collect(),
+            };
+
+            self.disabled_colliders.insert(entity, groups);
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn is_collider_enabled(&self, entity: Entity) -> bool {
+        !self.disabled_colliders.contains_key(entity)
+    }
+
+    // NOTE: Without physics there is nothing to freeze, disabling only skips the systems
+    #[cfg(not(feature = "physics"))]
+    pub fn set_enabled(&mut self, entity: Entity, enabled: bool) {
+        if !self.is_alive(entity) || self.is_enabled(entity) == enabled {
+            return;
+        }
+
+        if enabled {
+            self.remove_flag(entity, components::DISABLED);
+            self.disabled_container.remove(entity);
+        } else {
+            self.add_flag(entity, components::DISABLED);
+            self.disabled_container.insert(entity, DisabledComponent {});
+        }
+    }
+
+    pub fn is_enabled(&self, entity: Entity) -> bool {
+        !self.has_flag(entity, components::DISABLED)
+    }
+
+    /// A trigger which moves the respawn point of any player entering it.
+    #[cfg(feature = "physics")]
+    pub fn add_checkpoint(
+        &mut self,
+        entity: Entity,
+        component: TriggerComponent,
+        collider: Collider,
+    ) {
+        self.add_trigger(entity, component, collider);
+        self.add_flag(entity, components::CHECKPOINT);
+    }
+
+    #[inline]
+    pub fn add_trail(&mut self, entity: Entity, component: TrailComponent) {
+        self.trail_container.insert(entity, component);
+        self.add_flag(entity, components::TRAIL);
+    }
+
+    pub fn add_tilemap(&mut self, entity: Entity, component: TilemapComponent) {
+        self.tilemap_container.insert(entity, component);
+        self.add_flag(entity, components::TILEMAP);
+    }
+
+    /// (Re)generates fixed colliders covering the solid tiles of the entity's tilemap.
+    #[cfg(feature = "physics")]
+    pub fn build_tilemap_colliders(&mut self, entity: Entity) {
+        let Some(tilemap) = self.tilemap_container.get_mut(entity) else {
+            return;
+        };
+
+        for handle in tilemap.collider_handles.drain(..) {
+            self.collider_set.remove(
+                handle,
+                &mut self.island_manager,
+                &mut self.rigid_body_set,
+                true,
+            );
+        }
+
+        let size = tilemap.tile_size;
+        for (x, y, width, height) in tilemap.solid_rects() {
+            let (width, height) = (width as f32 * size, height as f32 * size);
+            let corner = tilemap.origin + vec2(x as f32, y as f32) * size;
+
+            let collider = ColliderBuilder::cuboid(width / 2.0, height / 2.0)
+                .translation(vector![corner.x + width / 2.0, corner.y + height / 2.0])
+                .user_data(entity.data().as_ffi() as u128)
+                .build();
+
+            tilemap
+                .collider_handles
+                .push(self.collider_set.insert(collider));
+        }
+    }
+
+    /// Entities whose colliders currently intersect the sensor `sensor_handle`.
+    #[cfg(feature = "physics")]
+    pub fn sensor_overlaps(
+        &self,
+        sensor_handle: ColliderHandle,
+    ) -> impl Iterator<Item = Entity> + '_ {
+        self.narrow_phase
+            .intersections_with(sensor_handle)
+            .filter(|(_h1, _h2, intersecting)| *intersecting)
+            .filter_map(move |(h1, h2, _intersecting)| {
+                self.collider_entity(if h1 == sensor_handle { h2 } else { h1 })
+            })
+    }
+
+    /// The first enabled entity with a collider containing the world space `point`.
+    #[cfg(feature = "physics")]
+    pub fn entity_at_point(&self, point: Vec2) -> Option<Entity> {
+        let point = point![point.x, point.y];
+
+        self.collider_set
+            .iter()
+            .filter(|(_handle, collider)| {
+                collider.shape().contains_point(collider.position(), &point)
+            })
+            .filter_map(|(handle, _collider)| self.collider_entity(handle))
+            .find(|entity| !self.entities[*entity].contains(components::DISABLED))
+    }
+
+    /*
+        The enabled entity closest to the world space `point` together with its distance,
+        optionally only among the entities matching `filter`.
+
+        NOTE: Entities with colliders are measured to the closest point of any of their
+              shapes, zero when `point` is inside one, and the rest to their position.
+    */
+    #[cfg(feature = "physics")]
+    pub fn nearest_entity(&self, point: Vec2, filter: Option<Query>) -> Option<(Entity, f32)> {
+        let query_point = point![point.x, point.y];
+
+        self.entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                filter.is_none_or(|query| query.is_subset_of(bitset))
+                    && !bitset.contains(components::DISABLED)
+            })
+            .filter_map(|(entity, _bitset)| {
+                let colliders = self
+                    .colliders_of(entity)
+                    .iter()
+                    .filter_map(|handle| self.collider_set.get(*handle))
+                    .map(|collider| {
+                        collider
+                            .shape()
+                            .distance_to_point(collider.position(), &query_point, true)
+                    })
+                    .reduce(f32::min);
+
+                let distance = match colliders {
+                    Some(distance) => distance,
+                    None => self.entity_position(entity)?.distance(point),
+                };
+
+                Some((entity, distance))
+            })
+            .min_by(|(_a, a), (_b, b)| a.total_cmp(b))
+    }
+
+    /*
+        Every enabled entity overlapping the world space box from `min` to `max`, touching
+        the edges included. Unlike `nearest_entity` this tests actual overlap rather than
+        distance, so an entity whose shape only pokes into the box counts.
+
+        NOTE: Entities with colliders are tested against their shapes and the rest by
+              whether their position is inside.
+    */
+    #[cfg(feature = "physics")]
+    pub fn entities_in_aabb(&self, min: Vec2, max: Vec2) -> Vec<Entity> {
+        let (min, max) = (min.min(max), min.max(max));
+
+        let half_extents = (max - min) * 0.5;
+        let center = min + half_extents;
+
+        let cuboid = Cuboid::new(vector![half_extents.x, half_extents.y]);
+        let cuboid_position = Isometry::translation(center.x, center.y);
+
+        self.entities
+            .iter()
+            .filter(|(_entity, bitset)| !bitset.contains(components::DISABLED))
+            .filter(|(entity, _bitset)| {
+                let colliders = self.colliders_of(*entity);
+
+                if colliders.is_empty() {
+                    return self.entity_position(*entity).is_some_and(|position| {
+                        position.cmpge(min).all() && position.cmple(max).all()
+                    });
+                }
+
+                colliders
+                    .iter()
+                    .filter_map(|handle| self.collider_set.get(*handle))
+                    .any(|collider| {
+                        rapier2d::parry::query::intersection_test(
+                            &cuboid_position,
+                            &cuboid,
+                            collider.position(),
+                            collider.shape(),
+                        )
+                        .unwrap_or(false)
+                    })
+            })
+            .map(|(entity, _bitset)| entity)
+            .collect()
+    }
+
+    /// Every collider of `entity`, the main one first. Empty for entities without any.
+    #[cfg(feature = "physics")]
+    pub fn colliders_of(&self, entity: Entity) -> &[ColliderHandle] {
+        self.collider_container
+            .get(entity)
+            .map_or(&[], |component| &component.collider_handles[..])
+    }
+
+    /// The entity owning `collider_handle`, if it is still alive.
+    #[cfg(feature = "physics")]
+    pub fn collider_entity(&self, collider_handle: ColliderHandle) -> Option<Entity> {
+        let collider = self.collider_set.get(collider_handle)?;
+        let entity = Entity::from(KeyData::from_ffi(collider.user_data as u64));
+
+        self.is_alive(entity).then_some(entity)
+    }
+
+    pub fn trigger_id(&self, entity: Entity) -> Option<u32> {
+        self.trigger_container.get(entity).map(|trigger| trigger.id)
+    }
+
+    /// Checks that every entity has exactly the component rows its flags claim, which
+    /// is what the `get_unchecked` calls in the systems rely on.
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let mut violations = vec![];
+
+        let label = |entity: Entity| self.label_container.get(entity).copied().unwrap_or("?");
+
+        macro_rules! check_container {
+            ($flag:expr, $container:expr) => {
+                self.entities.iter().for_each(|(entity, bitset)| {
+                    if bitset.contains($flag) && !$container.contains_key(entity) {
+                        violations.push(format!(
+                            "{entity:?} ({}) has {} but no row in {}",
+                            label(entity),
+                            stringify!($flag),
+                            stringify!($container),
+                        ));
+                    }
+                });
+
+                $container
+                    .keys()
+                    .for_each(|entity| match self.entities.get(entity) {
+                        None => violations.push(format!(
+                            "{} has a row for the removed entity {entity:?}",
+                            stringify!($container),
+                        )),
+                        Some(bitset) if !bitset.contains($flag) => violations.push(format!(
+                            "{entity:?} ({}) has a row in {} but not {}",
+                            label(entity),
+                            stringify!($container),
+                            stringify!($flag),
+                        )),
+                        Some(_) => {}
+                    });
+            };
+        }
+
+        check_container!(components::TEXTURE, self.texture_container);
+        #[cfg(feature = "physics")]
+        check_container!(components::RIGIDBODY, self.rigidbody_container);
+        #[cfg(feature = "physics")]
+        check_container!(components::COLLIDER, self.collider_container);
+        check_container!(components::PLAYER, self.player_container);
+        check_container!(components::TRIGGER, self.trigger_container);
+        check_container!(components::TRIGGER, self.trigger_overlaps);
+        check_container!(components::TRAIL, self.trail_container);
+        check_container!(components::DISABLED, self.disabled_container);
+        check_container!(components::DRAG_ZONE, self.drag_zone_container);
+        check_container!(components::TILEMAP, self.tilemap_container);
+        check_container!(components::CONVEYOR, self.conveyor_container);
+        check_container!(components::NET_INTERP, self.net_interp_container);
+        check_container!(components::SCREEN_SPACE, self.screen_space_container);
+        check_container!(components::HOMING, self.homing_container);
+        check_container!(components::SCRIPT, self.script_container);
+        check_container!(components::TIMER, self.timer_container);
+
+        // Flags which only make sense on top of other components
+        const REQUIREMENTS: [(Flag, Flag, &str); 9] = [
+            (
+                components::FIXED_COLLIDER,
+                components::RIGIDBODY | components::COLLIDER,
+                "FIXED_COLLIDER requires RIGIDBODY | COLLIDER",
+            ),
+            (
+                components::TRIGGER,
+                components::COLLIDER,
+                "TRIGGER requires COLLIDER",
+            ),
+            (
+                components::CHECKPOINT,
+                components::TRIGGER,
+                "CHECKPOINT requires TRIGGER",
+            ),
+            (
+                components::DRAG_ZONE,
+                components::COLLIDER,
+                "DRAG_ZONE requires COLLIDER",
+            ),
+            (
+                components::CONVEYOR,
+                components::COLLIDER,
+                "CONVEYOR requires COLLIDER",
+            ),
+            (
+                components::NET_INTERP,
+                components::RIGIDBODY,
+                "NET_INTERP requires RIGIDBODY",
+            ),
+            (
+                components::SCREEN_SPACE,
+                components::TEXTURE,
+                "SCREEN_SPACE requires TEXTURE",
+            ),
+            (
+                components::HOMING,
+                components::RIGIDBODY,
+                "HOMING requires RIGIDBODY",
+            ),
+            (
+                components::PLAYER,
+                components::RIGIDBODY | components::COLLIDER,
+                "PLAYER requires RIGIDBODY | COLLIDER",
+            ),
+        ];
+
+        self.entities.iter().for_each(|(entity, bitset)| {
+            if !self.label_container.contains_key(entity) {
+                violations.push(format!("{entity:?} has no label"));
+            }
+
+            REQUIREMENTS
+                .iter()
+                .filter(|(flag, required, _)| {
+                    bitset.contains(*flag) && !BitSet::new(*required).is_subset_of(bitset)
+                })
+                .for_each(|(_flag, _required, message)| {
+                    violations.push(format!("{entity:?} ({}): {message}", label(entity)));
+                });
+        });
+
+        #[cfg(feature = "physics")]
+        self.rigidbody_container
+            .iter()
+            .filter(|(_entity, component)| {
+                !self.rigid_body_set.contains(component.rigidbody_handle)
+            })
+            .for_each(|(entity, _component)| {
+                violations.push(format!(
+                    "{entity:?} ({}) has a dangling rigidbody handle",
+                    label(entity)
+                ));
+            });
+
+        #[cfg(feature = "physics")]
+        self.collider_container
+            .iter()
+            .filter(|(_entity, component)| {
+                component
+                    .collider_handles
+                    .iter()
+                    .any(|handle| !self.collider_set.contains(*handle))
+            })
+            .for_each(|(entity, _component)| {
+                violations.push(format!(
+                    "{entity:?} ({}) has a dangling collider handle",
+                    label(entity)
+                ));
+            });
+
+        #[cfg(feature = "physics")]
+        self.tilemap_container
+            .iter()
+            .filter(|(_entity, tilemap)| {
+                tilemap
+                    .collider_handles
+                    .iter()
+                    .any(|handle| !self.collider_set.contains(*handle))
+            })
+            .for_each(|(entity, _tilemap)| {
+                violations.push(format!(
+                    "{entity:?} ({}) has a dangling tilemap collider handle",
+                    label(entity)
+                ));
+            });
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /*
+        Every entity with both an `A` and a `B`, together with the two components.
+
+        NOTE: Like the systems, this skips disabled entities, unless one of the
+              components is the `DisabledComponent` itself.
+    */
+    pub fn query2<A: Component, B: Component>(&self) -> impl Iterator<Item = (Entity, &A, &B)> {
+        let query = Query::new(A::FLAG | B::FLAG);
+        let skip_disabled = !query.contains(components::DISABLED);
+
+        self.entities
+            .iter()
+            .filter(move |(_entity, bitset)| {
+                query.is_subset_of(bitset)
+                    && !(skip_disabled && bitset.contains(components::DISABLED))
+            })
+            .filter_map(|(entity, _bitset)| {
+                Some((
+                    entity,
+                    A::store(self).get(entity)?,
+                    B::store(self).get(entity)?,
+                ))
+            })
+    }
+
+    /// The body and main collider of `entity`, if it has both.
+    #[cfg(feature = "physics")]
+    pub fn physics_entity(&mut self, entity: Entity) -> Option<(&mut RigidBody, &mut Collider)> {
+        let rigidbody_handle = self.rigidbody_container.get(entity)?.rigidbody_handle;
+        let collider_handle = self.collider_container.get(entity)?.collider_handle();
+
+        Some((
+            self.rigid_body_set.get_mut(rigidbody_handle)?,
+            self.collider_set.get_mut(collider_handle)?,
+        ))
+    }
+
+    /*
+        Calls `f` with every enabled entity with both a rigidbody and a collider, together
+        with its body and main collider, in entity order.
+
+        NOTE: rapier only lends out more than one body or collider at a time through
+              `iter_mut`, which marks all of them as modified for the next step. Taking
+              a closure lets this resolve one entity at a time with `get_mut` instead.
+    */
+    #[cfg(feature = "physics")]
+    pub fn physics_entities(&mut self, mut f: impl FnMut(Entity, &mut RigidBody, &mut Collider)) {
+        const QUERY: Query = Query::new(components::RIGIDBODY | components::COLLIDER);
+
+        let matching = self
+            .entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .map(|(entity, _bitset)| entity)
+            .collect::<Vec<_>>();
+
+        for entity in matching {
+            if let Some((rigidbody, collider)) = self.physics_entity(entity) {
+                f(entity, rigidbody, collider);
+            }
+        }
+    }
+
+    /// Every entity with its label, flags and position, in iteration order.
+    pub fn dump(&self) -> Vec<(Entity, &str, BitSet, Option<Vec2>)> {
+        self.entities
+            .iter()
+            .map(|(entity, bitset)| {
+                let label = self.label_container.get(entity).copied().unwrap_or("?");
+                (entity, label, *bitset, self.entity_position(entity))
+            })
+            .collect()
+    }
+
+    /// Applies every queued command. Commands queued while flushing, e.g. by an
+    /// `init` closure, are applied as well.
+    pub fn flush_commands(&mut self) {
+        while !self.commands.is_empty() {
+            for command in std::mem::take(&mut self.commands.commands) {
+                match command {
+                    Command::Spawn { .. } if !self.can_spawn() => {
+                        self.deferred_spawns.push_back(command);
+                    }
+
+                    Command::Spawn { label, init } => {
+                        self.spawns_this_frame += 1;
+
+                        let entity = self.new_entity(label);
+                        init(self, entity);
+                    }
+
+                    Command::Despawn(entity) => {
+                        if !self.pending_despawns.contains(&entity) {
+                            self.pending_despawns.push(entity);
+                        }
+                    }
+
+                    // NOTE: An earlier command may have despawned the entity already
+                    Command::AddFlag(entity, flag) => {
+                        self.try_add_flag(entity, flag);
+                    }
+                    Command::RemoveFlag(entity, flag) => {
+                        self.try_remove_flag(entity, flag);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Removes every entity whose despawn was queued, see `Command`.
+    pub fn flush_despawns(&mut self) {
+        for entity in std::mem::take(&mut self.pending_despawns) {
+            self.remove_entity(entity);
+        }
+    }
+
+    /// Whether `entity` is queued to be removed at the end of the tick.
+    pub fn is_despawning(&self, entity: Entity) -> bool {
+        self.pending_despawns.contains(&entity)
+    }
+
+    fn can_spawn(&self) -> bool {
+        let within_budget = self
+            .max_spawns_per_frame
+            .is_none_or(|budget| self.spawns_this_frame < budget);
+
+        within_budget && self.entities.len() < MAX_ENTITIES
+    }
+
+    /// Restarts the spawn budget and applies the spawns deferred by the previous ticks.
+    fn begin_spawn_frame(&mut self) {
+        self.spawns_this_frame = 0;
+
+        // NOTE: The queue is empty between systems, so the deferred spawns go first
+        self.commands
+            .commands
+            .extend(self.deferred_spawns.drain(..));
+        self.flush_commands();
+    }
+
+    pub fn set_max_spawns_per_frame(&mut self, max_spawns: Option<usize>) {
+        self.max_spawns_per_frame = max_spawns;
+    }
+
+    /// Spawns waiting for a later tick because of the spawn budget.
+    pub fn deferred_spawns(&self) -> usize {
+        self.deferred_spawns.len()
+    }
+
+    pub fn add_tween(&mut self, tween: Tween<Game>) {
+        self.tweens.push(tween);
+    }
+
+    /// Tweens that have not finished yet.
+    pub fn tweens(&self) -> usize {
+        self.tweens.len()
+    }
+
+    /// Drops every running tween without completing it.
+    pub fn clear_tweens(&mut self) {
+        self.tweens.clear();
+    }
+
+    /// The events emitted during the last logic tick.
+    pub fn events(&self) -> &[GameEvent] {
+        &self.events
+    }
+
+    /*
+        Emits `GameEvent::FlagsChanged` whenever a flag is added to or removed from a
+        live entity, e.g. to invalidate caches keyed on flags.
+
+        NOTE: Off by default, since every component added or removed changes a flag.
+              Spawning starts from no flags and removing an entity emits nothing.
+    */
+    pub fn set_flag_events(&mut self, enabled: bool) {
+        self.flag_events = enabled;
+    }
+
+    /// Emits `GameEvent::Died` for `entity`, which is handled during the next logic tick.
+    pub fn kill(&mut self, entity: Entity) {
+        self.events.push(GameEvent::Died { entity });
+    }
+}
+
+// Physics Api
+impl Game {
+    #[cfg(feature = "physics")]
+    fn rigidbody(&self, entity: Entity) -> Option<&RigidBody> {
+        let handle = self.rigidbody_container.get(entity)?.rigidbody_handle;
+        self.rigid_body_set.get(handle)
+    }
+
+    #[cfg(feature = "physics")]
+    fn rigidbody_mut(&mut self, entity: Entity) -> Option<&mut RigidBody> {
+        let handle = self.rigidbody_container.get(entity)?.rigidbody_handle;
+        self.rigid_body_set.get_mut(handle)
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn position(&self, entity: Entity) -> Option<Vec2> {
+        let translation = self.rigidbody(entity)?.translation();
+        Some(vec2(translation.x, translation.y))
+    }
+
+    /// Moves the entity, which renders as a quick slide from its previous position
+    /// until the next tick. See `set_position_teleport` for a jump.
+    #[cfg(feature = "physics")]
+    pub fn set_position(&mut self, entity: Entity, position: Vec2) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.set_translation(vector![position.x, position.y], true);
+        }
+    }
+
+    /// Like `set_position`, but also forgets where the entity was so rendering
+    /// doesn't interpolate across the jump.
+    #[cfg(feature = "physics")]
+    pub fn set_position_teleport(&mut self, entity: Entity, position: Vec2) {
+        self.set_position(entity, position);
+        self.reset_interpolation(entity);
+    }
+
+    /// Makes the current position the one rendering interpolates from.
+    #[cfg(feature = "physics")]
+    pub fn reset_interpolation(&mut self, entity: Entity) {
+        let Some(component) = self.rigidbody_container.get_mut(entity) else {
+            return;
+        };
+
+        if let Some(rigidbody) = self.rigid_body_set.get(component.rigidbody_handle) {
+            component.previous_position = *rigidbody.position();
+        }
+    }
+
+    /// The position and rotation to draw the entity at, `alpha` of the way from
+    /// before the latest logic tick to now.
+    #[cfg(feature = "physics")]
+    pub fn interpolated_position(&self, entity: Entity, alpha: f32) -> Option<(Vec2, f32)> {
+        let component = self.rigidbody_container.get(entity)?;
+        let current = self
+            .rigid_body_set
+            .get(component.rigidbody_handle)?
+            .position();
+        let previous = &component.previous_position;
+
+        let translation = previous
+            .translation
+            .vector
+            .lerp(&current.translation.vector, alpha);
+        let rotation = previous.rotation.slerp(&current.rotation, alpha);
+
+        Some((vec2(translation.x, translation.y), rotation.angle()))
+    }
+
+    /*
+        World position of the collider, or of the rigidbody for entities without one.
+
+        NOTE: Fixed colliders keep their rigidbody at the origin and offset the collider
+              instead, so `position` would report the origin for those.
+    */
+    #[cfg(feature = "physics")]
+    pub fn entity_position(&self, entity: Entity) -> Option<Vec2> {
+        let collider = self
+            .collider_container
+            .get(entity)
+            .and_then(|component| self.collider_set.get(component.collider_handle()));
+
+        match collider {
+            Some(collider) => {
+                let translation = collider.translation();
+                Some(vec2(translation.x, translation.y))
+            }
+
+            None => self.position(entity),
+        }
+    }
+
+    // NOTE: Only rigidbodies and colliders have a position
+    #[cfg(not(feature = "physics"))]
+    pub fn entity_position(&self, _entity: Entity) -> Option<Vec2> {
+        None
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn linvel(&self, entity: Entity) -> Option<Vec2> {
+        let linvel = self.rigidbody(entity)?.linvel();
+        Some(vec2(linvel.x, linvel.y))
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn set_linvel(&mut self, entity: Entity, linvel: Vec2) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.set_linvel(vector![linvel.x, linvel.y], true);
+        }
+    }
+
+    /// How much of `gravity` pulls on the body: `0.0` floats in place and negative rises.
+    #[cfg(feature = "physics")]
+    pub fn set_gravity_scale(&mut self, entity: Entity, scale: f32) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.set_gravity_scale(scale, true);
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn gravity_scale(&self, entity: Entity) -> Option<f32> {
+        Some(self.rigidbody(entity)?.gravity_scale())
+    }
+
+    #[cfg(feature = "physics")]
+    fn set_axes_locked(&mut self, entity: Entity, axes: LockedAxes, locked: bool) {
+        let Some(component) = self.rigidbody_container.get_mut(entity) else {
+            return;
+        };
+
+        component.locked_axes.set(axes, locked);
+
+        if let Some(rigidbody) = self.rigid_body_set.get_mut(component.rigidbody_handle) {
+            rigidbody.set_locked_axes(component.locked_axes, true);
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn lock_rotation(&mut self, entity: Entity, locked: bool) {
+        self.set_axes_locked(entity, LockedAxes::ROTATION_LOCKED, locked);
+    }
+
+    /// Keeps the body from moving horizontally, e.g. for an elevator.
+    #[cfg(feature = "physics")]
+    pub fn lock_translation_x(&mut self, entity: Entity, locked: bool) {
+        self.set_axes_locked(entity, LockedAxes::TRANSLATION_LOCKED_X, locked);
+    }
+
+    /// Keeps the body from moving vertically, e.g. for something on a horizontal rail.
+    #[cfg(feature = "physics")]
+    pub fn lock_translation_y(&mut self, entity: Entity, locked: bool) {
+        self.set_axes_locked(entity, LockedAxes::TRANSLATION_LOCKED_Y, locked);
+    }
+
+    /// Angular velocity in radians per second, positive turning from +x towards +y.
+    #[cfg(feature = "physics")]
+    pub fn angvel(&self, entity: Entity) -> Option<f32> {
+        Some(self.rigidbody(entity)?.angvel())
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn set_angvel(&mut self, entity: Entity, angvel: f32) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.set_angvel(angvel, true);
+        }
+    }
+
+    /// Negative damping would add energy every step, so it is clamped to zero.
+    #[cfg(feature = "physics")]
+    pub fn set_linear_damping(&mut self, entity: Entity, damping: f32) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.set_linear_damping(damping.max(0.0));
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn set_angular_damping(&mut self, entity: Entity, damping: f32) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.set_angular_damping(damping.max(0.0));
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn apply_impulse(&mut self, entity: Entity, impulse: Vec2) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.apply_impulse(vector![impulse.x, impulse.y], true);
+        }
+    }
+
+    /// Like `apply_impulse`, but off center hits at `world_point` also set the body spinning.
+    #[cfg(feature = "physics")]
+    pub fn apply_impulse_at_point(&mut self, entity: Entity, impulse: Vec2, world_point: Vec2) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.apply_impulse_at_point(
+                vector![impulse.x, impulse.y],
+                point![world_point.x, world_point.y],
+                true,
+            );
+        }
+    }
+
+    /// Does nothing for bodies with locked rotations, like the player.
+    #[cfg(feature = "physics")]
+    pub fn apply_torque_impulse(&mut self, entity: Entity, torque_impulse: f32) {
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.apply_torque_impulse(torque_impulse, true);
+        }
+    }
+
+    /// Total mass of the body, zero meaning infinite, e.g. for fixed bodies.
+    #[cfg(feature = "physics")]
+    pub fn mass(&self, entity: Entity) -> Option<f32> {
+        Some(self.rigidbody(entity)?.mass())
+    }
+
+    /// Center of mass in the body's local space.
+    #[cfg(feature = "physics")]
+    pub fn center_of_mass(&self, entity: Entity) -> Option<Vec2> {
+        let com = self.rigidbody(entity)?.mass_properties().local_com;
+        Some(vec2(com.x, com.y))
+    }
+
+    /*
+        Mass added on top of what the colliders contribute through their density,
+        replacing any previously added mass.
+
+        NOTE: rapier only applies mass changes on the next step, so the mass properties
+              are recomputed right away for `mass` to reflect the change immediately.
+    */
+    #[cfg(feature = "physics")]
+    pub fn set_additional_mass(&mut self, entity: Entity, mass: f32) {
+        let Some(component) = self.rigidbody_container.get(entity) else {
+            return;
+        };
+
+        if let Some(rigidbody) = self.rigid_body_set.get_mut(component.rigidbody_handle) {
+            rigidbody.set_additional_mass(mass.max(0.0), true);
+            rigidbody.recompute_mass_properties_from_colliders(&self.collider_set);
+        }
+    }
+
+    /*
+        Resizes the colliders of `entity` to `scale` times the size they had when it was
+        first scaled, along with its sprite. The shapes keep their density, so the mass
+        grows and shrinks with them.
+
+        NOTE: Extra colliders move apart with the scale but the main collider stays where
+              it is on the body. Colliders added after the first call aren't scaled.
+    */
+    #[cfg(feature = "physics")]
+    pub fn set_collider_scale(&mut self, entity: Entity, scale: Vec2) {
+        if !(scale.x > 0.0 && scale.y > 0.0 && scale.is_finite()) {
+            return;
+        }
+
+        let Some(component) = self.collider_container.get(entity) else {
+            return;
+        };
+
+        if !self.collider_scales.contains_key(entity) {
+            let shapes = component
+                .collider_handles
+                .iter()
+                .filter_map(|handle| self.collider_set.get(*handle))
+                .map(|collider| {
+                    let offset = collider
+                        .position_wrt_parent()
+                        .copied()
+                        .unwrap_or_else(|| *collider.position());
+
+                    (collider.shared_shape().clone(), offset)
+                })
+                .collect();
+
+            let texture_size = self
+                .texture_container
+                .get(entity)
+                .map(|texture| texture.size);
+            self.collider_scales.insert(
+                entity,
+                ColliderScale {
+                    scale: Vec2::ONE,
+                    shapes,
+                    texture_size,
+                },
+            );
+        }
+
+        let base = &mut self.collider_scales[entity];
+        base.scale = scale;
+
+        let axes = vector![scale.x, scale.y];
+        for (i, (handle, (shape, offset))) in component
+            .collider_handles
+            .iter()
+            .zip(&base.shapes)
+            .enumerate()
+        {
+            let Some(collider) = self.collider_set.get_mut(*handle) else {
+                continue;
+            };
+
+            if let Some(shape) = scaled_shape(&**shape, &axes) {
+                collider.set_shape(shape);
+            }
+
+            if i > 0 {
+                let mut offset = *offset;
+                offset.translation.vector.component_mul_assign(&axes);
+                collider.set_position_wrt_parent(offset);
+            }
+        }
+
+        if let (Some(size), Some(texture)) =
+            (base.texture_size, self.texture_container.get_mut(entity))
+        {
+            texture.size = size * scale;
+        }
+
+        // NOTE: Like `set_additional_mass`, so that `mass` reflects the change right away
+        if let Some(rigidbody) = self
+            .rigidbody_container
+            .get(entity)
+            .and_then(|component| self.rigid_body_set.get_mut(component.rigidbody_handle))
+        {
+            rigidbody.recompute_mass_properties_from_colliders(&self.collider_set);
+        }
+    }
+
+    /// Gives every collider of `entity` the coefficients and combine rules of `material`.
+    #[cfg(feature = "physics")]
+    pub fn add_material(&mut self, entity: Entity, material: Material) {
+        let Some(component) = self.collider_container.get(entity) else {
+            return;
+        };
+
+        for handle in &component.collider_handles {
+            let Some(collider) = self.collider_set.get_mut(*handle) else {
+                continue;
+            };
+
+            collider.set_restitution(material.restitution);
+            collider.set_friction(material.friction);
+            collider.set_restitution_combine_rule(material.restitution_combine);
+            collider.set_friction_combine_rule(material.friction_combine);
+        }
+    }
+
+    /// The scale last passed to `set_collider_scale`, `1.0` for unscaled entities.
+    #[cfg(feature = "physics")]
+    pub fn collider_scale(&self, entity: Entity) -> Vec2 {
+        self.collider_scales
+            .get(entity)
+            .map_or(Vec2::ONE, |base| base.scale)
+    }
+
+    /*
+        Moves the center of mass to `center_of_mass`, in the body's local space, keeping
+        the mass and inertia as they are.
+
+        NOTE: This overrides the mass properties of the entity's collider, so changing its
+              density or mass afterwards resets the center of mass.
+    */
+    #[cfg(feature = "physics")]
+    pub fn set_center_of_mass(&mut self, entity: Entity, center_of_mass: Vec2) {
+        let (Some(rigidbody_component), Some(collider_component)) = (
+            self.rigidbody_container.get(entity),
+            self.collider_container.get(entity),
+        ) else {
+            return;
+        };
+
+        let Some(collider) = self
+            .collider_set
+            .get_mut(collider_component.collider_handle())
+        else {
+            return;
+        };
+
+        let com = point![center_of_mass.x, center_of_mass.y];
+        let local_com = collider
+            .position_wrt_parent()
+            .map_or(com, |isometry| isometry.inverse_transform_point(&com));
+
+        let props = collider.mass_properties();
+        collider.set_mass_properties(MassProperties::new(
+            local_com,
+            props.mass(),
+            props.principal_inertia(),
+        ));
+
+        if let Some(rigidbody) = self
+            .rigid_body_set
+            .get_mut(rigidbody_component.rigidbody_handle)
+        {
+            rigidbody.recompute_mass_properties_from_colliders(&self.collider_set);
+        }
+    }
+
+    /// The averaged world space normal of an active contact pair, pointing towards
+    /// the collider `towards`.
+    #[cfg(feature = "physics")]
+    fn pair_normal(pair: &ContactPair, towards: ColliderHandle) -> Option<Vec2> {
+        if !pair.has_any_active_contact {
+            return None;
+        }
+
+        // NOTE: Manifold normals point from `collider1` towards `collider2`
+        let sign = if pair.collider2 == towards { 1.0 } else { -1.0 };
+
+        let normal = pair
+            .manifolds
+            .iter()
+            .filter(|manifold| !manifold.data.solver_contacts.is_empty())
+            .fold(vector![0.0, 0.0], |sum, manifold| {
+                sum + manifold.data.normal
+            });
+
+        let normal = normal.try_normalize(f32::EPSILON)? * sign;
+        Some(vec2(normal.x, normal.y))
+    }
+
+    /// The world space normal of the contact between `a` and `b`, pointing from `b`
+    /// towards `a`, or `None` if they aren't touching. For a body resting on the
+    /// ground, `contact_normal(body, ground)` points up.
+    #[cfg(feature = "physics")]
+    pub fn contact_normal(&self, a: Entity, b: Entity) -> Option<Vec2> {
+        let handles_a = &self.collider_container.get(a)?.collider_handles;
+        let handles_b = &self.collider_container.get(b)?.collider_handles;
+
+        // NOTE: For compound entities, the first pair of shapes actually touching
+        handles_a.iter().find_map(|handle_a| {
+            handles_b.iter().find_map(|handle_b| {
+                let pair = self.narrow_phase.contact_pair(*handle_a, *handle_b)?;
+                Self::pair_normal(pair, *handle_a)
+            })
+        })
+    }
+
+    /// The normals of everything touching `entity`, each pointing towards `entity`.
+    #[cfg(feature = "physics")]
+    pub fn contact_normals(&self, entity: Entity) -> impl Iterator<Item = Vec2> + '_ {
+        self.colliders_of(entity)
+            .iter()
+            .copied()
+            .flat_map(move |handle| {
+                self.narrow_phase
+                    .contacts_with(handle)
+                    .filter_map(move |pair| Self::pair_normal(pair, handle))
+            })
+    }
+
+    /// How many bodies and collider pairs the last step had to deal with.
+    #[cfg(feature = "physics")]
+    pub fn physics_stats(&self) -> PhysicsStats {
+        PhysicsStats {
+            active_bodies: self.island_manager.active_dynamic_bodies().len()
+                + self.island_manager.active_kinematic_bodies().len(),
+            broad_phase_pairs: self.narrow_phase.contact_pairs().count()
+                + self.narrow_phase.intersection_pairs().count(),
+            contact_pairs: self
+                .narrow_phase
+                .contact_pairs()
+                .filter(|pair| pair.has_any_active_contact)
+                .count(),
+            intersection_pairs: self
+                .narrow_phase
+                .intersection_pairs()
+                .filter(|(_, _, intersecting)| *intersecting)
+                .count(),
+        }
+    }
+
+    /// Every other entity currently touching `entity`, each listed once.
+    #[cfg(feature = "physics")]
+    pub fn contacts_of(&self, entity: Entity) -> Vec<Entity> {
+        let mut contacts = vec![];
+
+        for &handle in self.colliders_of(entity) {
+            let others = self
+                .narrow_phase
+                .contacts_with(handle)
+                .filter(|pair| pair.has_any_active_contact)
+                .map(|pair| {
+                    if pair.collider1 == handle {
+                        pair.collider2
+                    } else {
+                        pair.collider1
+                    }
+                })
+                .filter_map(|other| self.collider_entity(other));
+
+            for other in others {
+                // NOTE: Compound entities touch through several colliders at once
+                if other != entity && !contacts.contains(&other) {
+                    contacts.push(other);
+                }
+            }
+        }
+
+        contacts
+    }
+
+    /// Whether `entity` is standing on something. Like the player controller this
+    /// assumes that gravity points down, towards positive y.
+    #[cfg(feature = "physics")]
+    pub fn is_grounded(&self, entity: Entity) -> bool {
+        self.contact_normals(entity)
+            .any(|normal| normal.y < -SURFACE_NORMAL_THRESHOLD)
+    }
+
+    /// Like `is_grounded`, but uses the feet sensor of players which have one.
+    #[cfg(feature = "physics")]
+    pub fn player_grounded(&self, entity: Entity) -> bool {
+        match self.player_container.get(entity) {
+            Some(player) if player.feet_sensor.is_some() => player.feet_grounded,
+            _ => self.is_grounded(entity),
+        }
+    }
+
+    /// The normal of a wall `entity` is touching, pointing away from the wall.
+    #[cfg(feature = "physics")]
+    pub fn wall_normal(&self, entity: Entity) -> Option<Vec2> {
+        self.contact_normals(entity)
+            .find(|normal| normal.x.abs() > SURFACE_NORMAL_THRESHOLD)
+    }
+
+    pub fn set_wind(&mut self, wind: Vec2) {
+        self.wind = wind;
+    }
+
+    /// Scales the wind for this entity, e.g. `0.0` for bodies it shouldn't move.
+    pub fn set_wind_drag(&mut self, entity: Entity, drag: f32) {
+        if self.is_alive(entity) {
+            self.wind_drag.insert(entity, drag);
+        }
+    }
+
+    /// How many substeps a physics step of `delta` needs for no player to move more
+    /// than half its size in one of them, within `PlayerTuning::max_substeps`.
+    #[cfg(feature = "physics")]
+    pub fn player_substeps(&self, delta: f32) -> usize {
+        let max_substeps = self.player_tuning.max_substeps.max(1);
+
+        self.player_container
+            .keys()
+            .filter(|entity| !self.entities[*entity].contains(components::DISABLED))
+            .map(|entity| {
+                /*
+                    SAFETY: We work on the premise that an entity with a PlayerComponent
+                            necessarily has a RigidBodyComponent and a ColliderComponent
+                */
+                let rigidbody = &self.rigid_body_set
+                    [unsafe { self.rigidbody_container.get_unchecked(entity) }.rigidbody_handle];
+                let collider = &self.collider_set
+                    [unsafe { self.collider_container.get_unchecked(entity) }.collider_handle()];
+
+                let half_size = collider.compute_aabb().half_extents().min();
+                let displacement = rigidbody.linvel().norm() * delta;
+
+                if half_size > 0.0 && displacement > half_size {
+                    (displacement / half_size).ceil().min(max_substeps as f32) as usize
+                } else {
+                    1
+                }
+            })
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// Entities with a rigidbody leaving these bounds emit `GameEvent::OutOfBounds`.
+    pub fn set_world_bounds(&mut self, bounds: Option<Rect>) {
+        self.world_bounds = bounds;
+    }
+
+    /// Caps the speed of every dynamic body after each physics step, `None` for no cap.
+    pub fn set_max_speed(&mut self, max_speed: Option<f32>) {
+        self.max_speed = max_speed.map(|max_speed| max_speed.max(0.0));
+    }
+
+    /// Scales the delta seen by every logic system. Clamped to `0..=MAX_TIME_SCALE`,
+    /// where 0 pauses the simulation.
+    pub fn set_time_scale(&mut self, time_scale: f32) {
+        self.time_scale = if time_scale.is_nan() {
+            0.0
+        } else {
+            time_scale.clamp(0.0, MAX_TIME_SCALE)
+        };
+    }
+
+    /*
+        NOTE: More iterations make tall stacks settle instead of jittering or sinking,
+              at a cost. The contact solver runs each loop once per iteration over every
+              active contact, so its share of the frame grows linearly with these counts.
+              The rapier defaults are 4 velocity, 8 friction and 1 stabilization iteration.
+    */
+    #[cfg(feature = "physics")]
+    pub fn set_solver_iterations(
+        &mut self,
+        velocity: usize,
+        friction: usize,
+        stabilization: usize,
+    ) {
+        self.integration_parameters.max_velocity_iterations = velocity.max(1);
+        self.integration_parameters.max_velocity_friction_iterations = friction.max(1);
+        self.integration_parameters.max_stabilization_iterations = stabilization.max(1);
+    }
+
+    /// Replaces every integration parameter with the ones of `preset`.
+    #[cfg(feature = "physics")]
+    pub fn apply_physics_preset(&mut self, preset: PhysicsPreset) {
+        self.integration_parameters = preset.integration_parameters();
+    }
+
+    /// A checksum of the simulation. Two simulations fed the same inputs produce the same
+    /// hash every tick, so comparing hashes detects desyncs. Floats are quantized first so
+    /// that noise far below anything visible doesn't count as a divergence.
+    pub fn state_hash(&self) -> u64 {
+        const QUANTIZATION: f32 = 1_000.0;
+        let quantize = |value: f32| (value * QUANTIZATION).round() as i64;
+
+        let mut hasher = Fnv1aHasher::default();
+
+        #[cfg(feature = "physics")]
+        {
+            quantize(self.gravity.x).hash(&mut hasher);
+            quantize(self.gravity.y).hash(&mut hasher);
+        }
+        quantize(self.wind.x).hash(&mut hasher);
+        quantize(self.wind.y).hash(&mut hasher);
+        self.rng.state().hash(&mut hasher);
+
+        self.entities.iter().for_each(|(entity, bitset)| {
+            entity.data().as_ffi().hash(&mut hasher);
+            bitset.bits().hash(&mut hasher);
+
+            #[cfg(feature = "physics")]
+            if let Some(rigidbody) = self.rigidbody(entity) {
+                let pos = rigidbody.position();
+                let linvel = rigidbody.linvel();
+
+                quantize(pos.translation.x).hash(&mut hasher);
+                quantize(pos.translation.y).hash(&mut hasher);
+                quantize(pos.rotation.angle()).hash(&mut hasher);
+                quantize(linvel.x).hash(&mut hasher);
+                quantize(linvel.y).hash(&mut hasher);
+                quantize(rigidbody.angvel()).hash(&mut hasher);
+                rigidbody.is_sleeping().hash(&mut hasher);
+            }
+        });
+
+        hasher.finish()
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn respawn_player(&mut self, entity: Entity) {
+        let respawn_point = match self.player_container.get(entity) {
+            Some(player) => player.respawn_point,
+            None => return,
+        };
+
+        if let Some(rigidbody) = self.rigidbody_mut(entity) {
+            rigidbody.set_translation(vector![respawn_point.x, respawn_point.y], true);
+            rigidbody.set_linvel(vector![0.0, 0.0], true);
+            rigidbody.set_angvel(0.0, true);
+        }
+
+        self.reset_interpolation(entity);
+    }
+}
+
+/*
+    Position snapshots
+
+    NOTE: Layout, all little endian:
+            u32                 number of records
+            per record:
+              u64               entity, its `Game::external_id_of`
+              i16, i16          position in steps of `NET_POSITION_QUANTUM`
+              i16               rotation, with `i16::MAX` standing for pi
+
+          External ids line up between machines which spawned the same entities in
+          the same order, whichever slots their removals freed up for reuse. Positions
+          saturate outside of roughly +-8192 units, 0.25 * 2^15, around the world origin.
+*/
+#[cfg(feature = "physics")]
+const NET_RECORD_SIZE: usize = 8 + 2 + 2 + 2;
+
+// Network Api
+impl Game {
+    /// Quantized positions and rotations of every non-fixed rigidbody.
+    #[cfg(feature = "physics")]
+    pub fn encode_positions(&self) -> Vec<u8> {
+        const QUERY: Query = Query::new(components::RIGIDBODY);
+
+        let records = self
+            .entities
+            .iter()
+            .filter(|(_entity, bitset)| QUERY.is_subset_of(bitset))
+            .filter_map(|(entity, _bitset)| {
+                // NOTE: Fixed sensors like triggers have a fixed rigidbody but no FIXED_COLLIDER
+                let rigidbody = self.rigidbody(entity)?;
+                let external_id = self.external_id_of(entity)?;
+                (!rigidbody.is_fixed()).then(|| (external_id, *rigidbody.position()))
+            })
+            .collect::<Vec<_>>();
+
+        let mut bytes = Vec::with_capacity(4 + records.len() * NET_RECORD_SIZE);
+        bytes.extend_from_slice(&(records.len() as u32).to_le_bytes());
+
+        for (external_id, isometry) in records {
+            let translation = isometry.translation;
+            let rotation = isometry.rotation.angle() / PI * i16::MAX as f32;
+
+            // NOTE: Float to int casts saturate, which is exactly what we want here
+            bytes.extend_from_slice(&external_id.to_le_bytes());
+            bytes.extend_from_slice(
+                &((translation.x / NET_POSITION_QUANTUM).round() as i16).to_le_bytes(),
+            );
+            bytes.extend_from_slice(
+                &((translation.y / NET_POSITION_QUANTUM).round() as i16).to_le_bytes(),
+            );
+            bytes.extend_from_slice(&(rotation.round() as i16).to_le_bytes());
+        }
+
+        bytes
+    }
+
+    /// Applies a snapshot from `encode_positions` and returns how many entities it
+    /// moved. Entities with a `NetInterpComponent` queue the snapshot instead. Records
+    /// of unknown entities are skipped. Returns `None` without changing anything if
+    /// `bytes` is malformed.
+    #[cfg(feature = "physics")]
+    pub fn decode_positions(&mut self, bytes: &[u8]) -> Option<usize> {
+        let (count, records) = bytes.split_first_chunk::<4>()?;
+        let count = u32::from_le_bytes(*count) as usize;
+
+        if records.len() != count.checked_mul(NET_RECORD_SIZE)? {
+            return None;
+        }
+
+        let mut applied = 0;
+
+        for record in records.chunks_exact(NET_RECORD_SIZE) {
+            let i16_at = |at: usize| i16::from_le_bytes([record[at], record[at + 1]]) as f32;
+
+            let external_id = u64::from_le_bytes(record[0..8].try_into().unwrap());
+            let Some(entity) = self.entity_by_external_id(external_id) else {
+                continue;
+            };
+
+            let position = vec2(i16_at(8), i16_at(10)) * NET_POSITION_QUANTUM;
+            let rotation = i16_at(12) / i16::MAX as f32 * PI;
+
+            if let Some(interp) = self.net_interp_container.get_mut(entity) {
+                interp.push(position, rotation, self.net_time);
+                applied += 1;
+            } else if let Some(rigidbody) = self.rigidbody_mut(entity) {
+                rigidbody.set_translation(vector![position.x, position.y], true);
+                rigidbody.set_rotation(rotation, true);
+                applied += 1;
+            }
+        }
+
+        Some(applied)
+    }
+}
+
+// Resources Api
+impl Game {
+    pub fn resources(&self) -> &Resources {
+        &self.resources
+    }
+
+    pub fn reset_resources(&mut self) {
+        self.resources = Resources::default();
+    }
+
+    pub fn score(&self) -> u64 {
+        self.resources.score
+    }
+
+    pub fn add_score(&mut self, points: u64) {
+        self.resources.score = self.resources.score.saturating_add(points);
+    }
+
+    pub fn lives(&self) -> u32 {
+        self.resources.lives
+    }
+
+    pub fn set_lives(&mut self, lives: u32) {
+        self.resources.lives = lives;
+    }
+
+    pub fn is_out_of_lives(&self) -> bool {
+        self.resources.lives == 0
+    }
+
+    pub fn elapsed(&self) -> f64 {
+        self.resources.elapsed
+    }
+}
+
+// Random Api
+impl Game {
+    pub fn seed_rng(&mut self, seed: u64) {
+        self.rng = Rng::new(seed);
+    }
+
+    /// The generator's whole state, for snapshots. See `set_rng_state`.
+    pub fn rng_state(&self) -> u64 {
+        self.rng.state()
+    }
+
+    pub fn set_rng_state(&mut self, state: u64) {
+        self.rng = Rng::from_state(state);
+    }
+
+    /// Uniform in `low..high`, or `low` when the range is empty.
+    pub fn random_range(&mut self, low: f32, high: f32) -> f32 {
+        self.rng.range(low, high)
+    }
+
+    /// A uniformly distributed point inside `rect`.
+    pub fn random_vec2_in(&mut self, rect: Rect) -> Vec2 {
+        let x = self.rng.range(rect.left(), rect.right());
+        let y = self.rng.range(rect.top(), rect.bottom());
+
+        vec2(x, y)
+    }
+
+    /// A direction of length one, uniformly distributed around the circle.
+    pub fn random_unit_vec2(&mut self) -> Vec2 {
+        let angle = self.rng.range(0.0, std::f32::consts::TAU);
+        vec2(angle.cos(), angle.sin())
+    }
+
+    /// Reseeds the generator with `seed` and scatters fixed platforms that don't
+    /// overlap, and dynamic balls outside of them, within `bounds`. Returns everything
+    /// spawned, which can be fewer than asked for when there isn't room for more or
+    /// `MAX_ENTITIES` is reached.
+    #[cfg(feature = "physics")]
+    pub fn generate_random_level(
+        &mut self,
+        seed: u64,
+        num_platforms: usize,
+        num_balls: usize,
+        bounds: Rect,
+    ) -> Vec<Entity> {
+        // Tries per platform or ball before giving up on finding a free spot for it
+        const ATTEMPTS: usize = 32;
+        // Free space kept around every platform
+        const MARGIN: f32 = 10.0;
+        const BALL_RADIUS: f32 = 5.0;
+
+        self.seed_rng(seed);
+
+        let mut spawned = vec![];
+        let mut platforms: Vec<Rect> = vec![];
+
+        for _ in 0..num_platforms {
+            if self.entities.len() >= MAX_ENTITIES {
+                return spawned;
+            }
+
+            for _ in 0..ATTEMPTS {
+                let half_extents =
+                    vec2(self.random_range(30.0, 120.0), self.random_range(5.0, 10.0));
+                let area = Rect::new(
+                    bounds.x + half_extents.x,
+                    bounds.y + half_extents.y,
+                    bounds.w - half_extents.x * 2.0,
+                    bounds.h - half_extents.y * 2.0,
+                );
+
+                if area.w < 0.0 || area.h < 0.0 {
+                    continue;
+                }
+
+                let center = self.random_vec2_in(area);
+                let rect = Rect::new(
+                    center.x - half_extents.x,
+                    center.y - half_extents.y,
+                    half_extents.x * 2.0,
+                    half_extents.y * 2.0,
+                );
+
+                let padded = Rect::new(
+                    rect.x - MARGIN,
+                    rect.y - MARGIN,
+                    rect.w + MARGIN * 2.0,
+                    rect.h + MARGIN * 2.0,
+                );
+
+                if platforms.iter().any(|platform| platform.overlaps(&padded)) {
+                    continue;
+                }
+
+                let entity = self.new_entity("Platform");
+                self.add_fixed_collider(
+                    entity,
+                    ColliderBuilder::cuboid(half_extents.x, half_extents.y)
+                        .translation(vector![center.x, center.y])
+                        .build(),
+                );
+
+                platforms.push(rect);
+                spawned.push(entity);
+                break;
+            }
+        }
+
+        let area = Rect::new(
+            bounds.x + BALL_RADIUS,
+            bounds.y + BALL_RADIUS,
+            bounds.w - BALL_RADIUS * 2.0,
+            bounds.h - BALL_RADIUS * 2.0,
+        );
+
+        if area.w < 0.0 || area.h < 0.0 {
+            return spawned;
+        }
+
+        for _ in 0..num_balls {
+            if self.entities.len() >= MAX_ENTITIES {
+                return spawned;
+            }
+
+            for _ in 0..ATTEMPTS {
+                let position = self.random_vec2_in(area);
+                let ball = Rect::new(
+                    position.x - BALL_RADIUS,
+                    position.y - BALL_RADIUS,
+                    BALL_RADIUS * 2.0,
+                    BALL_RADIUS * 2.0,
+                );
+
+                if platforms.iter().any(|platform| platform.overlaps(&ball)) {
+                    continue;
+                }
+
+                let entity = self.new_entity("Ball");
+                self.add_physics(
+                    entity,
+                    RigidBodyBuilder::dynamic()
+                        .translation(vector![position.x, position.y])
+                        .build(),
+                    ColliderBuilder::ball(BALL_RADIUS).restitution(0.8).build(),
+                );
+
+                if let Some(texture) = self.spawn_texture {
+                    self.add_texture(
+                        entity,
+                        TextureComponent {
+                            texture,
+                            size: Vec2::splat(BALL_RADIUS * 2.0),
+                            color: WHITE,
+                            render_scale: Vec2::ONE,
+                            rotation_offset: 0.0,
+                        },
+                    );
+                }
+
+                spawned.push(entity);
+                break;
+            }
+        }
+
+        spawned
+    }
+}
+
+// Spawning Api
+impl Game {
+    /// What `spawn_shape` and the shapes spawned from the console are made of from now on.
+    #[cfg(feature = "physics")]
+    pub fn set_default_material(&mut self, material: Material) {
+        self.default_material = material;
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn default_material(&self) -> Material {
+        self.default_material
+    }
+
+    /// A dynamic `shape` at `position` made of the default material, textured with the
+    /// spawn texture when there is one. `None` once `MAX_ENTITIES` is reached.
+    #[cfg(feature = "physics")]
+    pub fn spawn_shape(&mut self, shape: SpawnShape, position: Vec2) -> Option<Entity> {
+        if self.entities.len() >= MAX_ENTITIES {
+            return None;
+        }
+
+        let (label, collider, size) = match shape {
+            SpawnShape::Ball => ("Ball", ColliderBuilder::ball(5.0), vec2(10.0, 10.0)),
+            SpawnShape::Box => ("Box", ColliderBuilder::cuboid(10.0, 10.0), vec2(20.0, 20.0)),
+        };
+
+        let entity = self.new_entity(label);
+        self.add_physics(
+            entity,
+            RigidBodyBuilder::dynamic()
+                .translation(vector![position.x, position.y])
+                .build(),
+            collider.build(),
+        );
+        self.add_material(entity, self.default_material);
+
+        if let Some(texture) = self.spawn_texture {
+            self.add_texture(
+                entity,
+                TextureComponent {
+                    texture,
+                    size,
+                    color: WHITE,
+                    render_scale: Vec2::ONE,
+                    rotation_offset: 0.0,
+                },
+            );
+        }
+
+        Some(entity)
+    }
+
+    /// Like `spawn_shape`, at the world position under `point` on a screen of `screen_size` pixels.
+    #[cfg(feature = "physics")]
+    pub fn spawn_at_screen_point(
+        &mut self,
+        shape: SpawnShape,
+        point: Vec2,
+        screen_size: Vec2,
+    ) -> Option<Entity> {
+        let position = screen_to_world(&self.camera, point, screen_size);
+        self.spawn_shape(shape, position)
+    }
+
+    /// Like `spawn_shape`, under the mouse. `None` while the mouse is over the editor.
+    #[cfg(feature = "physics")]
+    pub fn spawn_at_mouse(&mut self, shape: SpawnShape) -> Option<Entity> {
+        #[cfg(feature = "editor")]
+        if self.egui_wants_pointer {
+            return None;
+        }
+
+        let screen_size = vec2(screen_width(), screen_height());
+        self.spawn_at_screen_point(shape, mouse_position().into(), screen_size)
+    }
+}
+
+// Console Api
+impl Game {
+    pub fn execute(&mut self, command: ConsoleCommand) {
+        match command {
+            #[cfg(feature = "physics")]
+            ConsoleCommand::Spawn { shape, position } => {
+                let entity = match position {
+                    Some(position) => self.spawn_shape(shape, position),
+                    None => self.spawn_at_mouse(shape),
+                };
+
+                match entity {
+                    Some(entity) => self.console.print(format!("spawned {entity:?}")),
+                    None => self.console.print("couldn't spawn there"),
+                }
+            }
+
+            #[cfg(feature = "physics")]
+            ConsoleCommand::Gravity(gravity) => self.gravity = vector![gravity.x, gravity.y],
+            #[cfg(not(feature = "physics"))]
+            ConsoleCommand::Spawn { .. } | ConsoleCommand::Gravity(_) => {
+                self.console.print("this command needs the physics feature")
+            }
+            ConsoleCommand::Wind(wind) => self.set_wind(wind),
+            ConsoleCommand::TimeScale(time_scale) => self.set_time_scale(time_scale),
+
+            ConsoleCommand::Dump => {
+                let lines = self
+                    .dump()
+                    .into_iter()
+                    .map(|(entity, label, bitset, position)| {
+                        format!("{entity:?} {label} {bitset:?} {position:?}")
+                    })
+                    .collect::<Vec<_>>();
+
+                lines.into_iter().for_each(|line| self.console.print(line));
+            }
+
+            ConsoleCommand::Clear => self.console.clear(),
+            ConsoleCommand::Help => self.console.print(console::HELP),
+        }
+    }
+
+    /*
+        NOTE: Runs once per rendered frame, since `run_logic_systems` runs zero or more
+              times per frame and would see a single key press as many or none.
+    */
+    pub fn debug_keys_system(&mut self) {
+        if is_key_pressed(self.keys[&Actions::DumpWorld]) {
+            for (entity, label, bitset, position) in self.dump() {
+                println!("{entity:?} {label} {bitset:?} {position:?}");
+            }
+        }
+
+        if is_key_pressed(self.keys[&Actions::ToggleProfiler]) {
+            self.profiler.enabled = !self.profiler.enabled;
+        }
+    }
+
+    pub fn console_system(&mut self) {
+        if is_key_pressed(self.keys[&Actions::ToggleConsole]) {
+            self.console.open = !self.console.open;
+        }
+
+        if !self.console.open {
+            return;
+        }
+
+        if let Some(line) = self.console.read_input() {
+            match console::parse(&line) {
+                Ok(command) => self.execute(command),
+                Err(error) => self.console.print(error),
+            }
+        }
+    }
+}
+
+// Config Api
+impl Game {
+    pub fn save_bindings(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut output = String::from("# Key bindings, one `Action=KeyCode` per line\n");
+
+        for action in Actions::all() {
+            if let Some(key_code) = self.keys.get(action) {
+                output += &format!("{}={}\n", action.name(), config::keycode_name(*key_code));
+            }
+        }
+
+        fs::write(path, output)
+    }
+
+    /// Replaces the bindings mentioned in the file. On error nothing is changed,
+    /// so a missing or malformed file leaves the built-in bindings in place.
+    pub fn load_bindings(&mut self, path: impl AsRef<Path>) -> io::Result<()> {
+        let source = fs::read_to_string(path)?;
+
+        let mut keys = self.keys.clone();
+        for entry in config::parse(&source) {
+            let malformed = |line: &str| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("malformed binding {line:?}"),
+                )
+            };
+
+            let (action_name, key_name) = entry.map_err(malformed)?;
+
+            let (Some(action), Some(key_code)) = (
+                Actions::from_name(action_name),
+                config::keycode_from_name(key_name),
+            ) else {
+                return Err(malformed(&format!("{action_name}={key_name}")));
+            };
+
+            keys.insert(action, key_code);
+        }
+
+        self.keys = keys;
+        Ok(())
+    }
+
+    /// Rebinds `action` and persists all bindings to `BINDINGS_PATH`.
+    pub fn set_binding(&mut self, action: Actions, key_code: KeyCode) {
+        self.keys.insert(action, key_code);
+
+        if let Err(err) = self.save_bindings(BINDINGS_PATH) {
+            eprintln!("Could not save key bindings: {err}");
+            self.toast(
+                format!("Could not save key bindings: {err}"),
+                ERROR_TOAST_TIME,
+            );
+        }
+    }
+}
+
+// Camera Api
+impl Game {
+    /// Makes the camera follow `entity`, or the first player again for `None`.
+    pub fn set_camera_target(&mut self, entity: Option<Entity>) {
+        self.camera_target_entity = entity;
+    }
+
+    /// The entity the camera follows, if any. Falls back to the first enabled player
+    /// once the chosen target is removed.
+    pub fn camera_target(&self) -> Option<Entity> {
+        self.camera_target_entity
+            .filter(|entity| self.is_alive(*entity))
+            .or_else(|| {
+                self.player_container
+                    .keys()
+                    .find(|entity| !self.entities[*entity].contains(components::DISABLED))
+            })
+    }
+}
+
+// Logic Systems
+impl Game {
+    /*
+        NOTE: Runs once per frame, before the fixed steps. A frame can have no steps or
+              several, so presses are kept until a step has used them and held keys
+              apply to every step of the frame.
+    */
+    #[cfg(feature = "physics")]
+    pub fn player_input_system(&mut self) {
+        // NOTE: Typing into the console shouldn't move the player
+        if self.console.open {
+            self.player_input = PlayerInput::default();
+            return;
+        }
+
+        let mut movement = Vec2::ZERO;
+
+        if is_key_down(self.keys[&Actions::MoveRight]) {
+            movement.x -= 1.0;
+        }
+
+        if is_key_down(self.keys[&Actions::MoveLeft]) {
+            movement.x += 1.0;
+        }
+
+        if is_key_down(self.keys[&Actions::MoveDown]) {
+            movement.y += 1.0;
+        }
+
+        self.player_input.movement = movement;
+        self.player_input.jump |= is_key_pressed(self.keys[&Actions::MoveUp]);
+        self.player_input.dash |= is_key_pressed(self.keys[&Actions::Dash]);
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn player_movement_system(&mut self, delta: f32) {
+        let input = self.player_input;
+
+        self.player_input.jump = false;
+        self.player_input.dash = false;
+
+        self.apply_player_input(input, delta);
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn apply_player_input(&mut self, input: PlayerInput, delta: f32) {
+        let tuning = self.player_tuning;
+
+        let movement = vector![input.movement.x, input.movement.y];
+        let force = movement.try_normalize(0.1).unwrap_or(vector![0.0, 0.0]) * tuning.speed * delta;
+
+        let players = self
+            .player_container
+            .keys()
+            .filter(|entity| !self.entities[*entity].contains(components::DISABLED))
+            .map(|entity| {
+                // NOTE: Wall interactions only happen in the air
+                let wall_normal = if self.player_grounded(entity) {
+                    None
+                } else {
+                    self.wall_normal(entity)
+                };
+
+                (entity, wall_normal)
+            })
+            .collect::<Vec<_>>();
+
+        players.into_iter().for_each(|(entity, wall_normal)| {
+            /*
+                SAFETY: `players` was collected from the keys of `player_container`
+            */
+            let player = unsafe { self.player_container.get_unchecked_mut(entity) };
+
+            player.dash_cooldown = (player.dash_cooldown - delta).max(0.0);
+            player.dash_timer = (player.dash_timer - delta).max(0.0);
+
+            if input.movement.x != 0.0 {
+                player.facing = input.movement.x.signum();
+            }
+
+            let dash = (input.dash && player.dash_cooldown <= 0.0).then(|| {
+                player.dash_cooldown = tuning.dash_cooldown;
+                player.dash_timer = tuning.dash_duration;
+
+                input
+                    .movement
+                    .try_normalize()
+                    .unwrap_or(vec2(player.facing, 0.0))
+            });
+
+            let Some((rigidbody, _collider)) = self.physics_entity(entity) else {
+                return;
+            };
+
+            let linvel = rigidbody.linvel();
+            let mut new_linvel = vector![
+                linvel.x + force.x,
+                if input.jump {
+                    -tuning.jump_velocity
+                } else {
+                    linvel.y
+                } + force.y
+            ];
+
+            match wall_normal {
+                Some(normal) if input.jump => {
+                    new_linvel.x = normal.x.signum() * tuning.wall_jump_velocity.x;
+                    new_linvel.y = -tuning.wall_jump_velocity.y;
+                }
+
+                // Pressing into the wall
+                Some(normal) if input.movement.x * normal.x < 0.0 => {
+                    new_linvel.y = new_linvel.y.min(tuning.wall_slide_speed);
+                }
+
+                _ => {}
+            }
+
+            if let Some(direction) = dash {
+                // NOTE: Replaces the velocity outright, damping slows it down again afterwards
+                new_linvel = vector![direction.x, direction.y] * tuning.dash_speed;
+            }
+
+            rigidbody.set_linvel(new_linvel, true);
+
+            let _is_falling = new_linvel.y > 0.0;
+
+            // collider.set_mass(if is_falling { 300.0 } else { 10.0 });
+            //let mass = collider.mass();
+
+            // println!("{mass}");
+        });
+    }
+
+    /*
+        NOTE: CCD alone doesn't keep a fast player out of thin walls, so while a player
+              moves far enough per tick to tunnel the whole world is stepped in smaller
+              substeps. Everything else only pays for it during e.g. a dash.
+    */
+    #[cfg(feature = "physics")]
+    pub fn physics_system(&mut self, delta: f32) {
+        let substeps = self.player_substeps(delta);
+        self.integration_parameters.dt = delta / substeps as f32;
+
+        // NOTE: Also catches velocities set since the last step, before they move anything
+        self.clamp_velocities();
+
+        for _ in 0..substeps {
+            self.physics_pipeline.step(
+                &self.gravity,
+                &self.integration_parameters,
+                &mut self.island_manager,
+                &mut self.broad_phase,
+                &mut self.narrow_phase,
+                &mut self.rigid_body_set,
+                &mut self.collider_set,
+                &mut self.impulse_joint_set,
+                &mut self.multibody_joint_set,
+                &mut self.ccd_solver,
+                &self.physics_hooks,
+                &self.event_handler,
+            );
+
+            self.clamp_velocities();
+        }
+    }
+
+    /*
+        NOTE: Overlapping bodies are pushed apart as fast as the solver sees fit, which
+              can fling them across the world in a single step. Anything over `max_speed`
+              is slowed down to it and NaN or infinite velocities are zeroed before they
+              spread to everything the body touches, reported as an event rather than
+              printed since a broken body can stay broken for every tick.
+    */
+    #[cfg(feature = "physics")]
+    fn clamp_velocities(&mut self) {
+        let max_speed = self.max_speed;
+
+        for (entity, component) in self.rigidbody_container.iter() {
+            let Some(rigidbody) = self.rigid_body_set.get_mut(component.rigidbody_handle) else {
+                continue;
+            };
+
+            if !rigidbody.is_dynamic() {
+                continue;
+            }
+
+            let linvel = *rigidbody.linvel();
+            let angvel = rigidbody.angvel();
+
+            if !(linvel.x.is_finite() && linvel.y.is_finite() && angvel.is_finite()) {
+                self.events.push(GameEvent::NonFiniteVelocity { entity });
+
+                rigidbody.set_linvel(vector![0.0, 0.0], true);
+                rigidbody.set_angvel(0.0, true);
+                continue;
+            }
+
+            if let Some(max_speed) = max_speed {
+                let speed = linvel.norm();
+
+                if speed > max_speed {
+                    rigidbody.set_linvel(linvel * (max_speed / speed), true);
+                }
+            }
+        }
+    }
+
+    /*
+        NOTE: Rather than changing the damping of the bodies inside (and having to restore
+              it on exit), the zone applies its effect on top every tick a body is inside.
+              The velocity scaling matches what rapier does for `linear_damping`.
+    */
+    #[cfg(feature = "physics")]
+    pub fn drag_zone_system(&mut self, delta: f32) {
+        const QUERY: Query = Query::new(components::DRAG_ZONE | components::COLLIDER);
+
+        let affected = self
+            .entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .flat_map(|(zone, _bitset)| {
+                let handle =
+                    unsafe { self.collider_container.get_unchecked(zone) }.collider_handle();
+                let drag = unsafe { self.drag_zone_container.get_unchecked(zone) };
+
+                self.sensor_overlaps(handle)
+                    .map(|entity| (entity, drag.linear_damping, drag.buoyancy))
+            })
+            .collect::<Vec<_>>();
+
+        let up = -self.gravity.try_normalize(0.0).unwrap_or(vector![0.0, 1.0]);
+
+        affected
+            .into_iter()
+            .for_each(|(entity, linear_damping, buoyancy)| {
+                let rigidbody = match self.rigidbody_mut(entity) {
+                    Some(rigidbody) if rigidbody.is_dynamic() => rigidbody,
+                    _ => return,
+                };
+
+                let linvel = *rigidbody.linvel() / (1.0 + delta * linear_damping);
+                rigidbody.set_linvel(linvel, true);
+
+                let impulse = up * buoyancy * rigidbody.mass() * delta;
+                rigidbody.apply_impulse(impulse, true);
+            });
+    }
+
+    /// Remembers where every body is before the tick moves it, for `interpolated_position`.
+    #[cfg(feature = "physics")]
+    pub fn store_previous_positions(&mut self) {
+        for (_entity, component) in self.rigidbody_container.iter_mut() {
+            if let Some(rigidbody) = self.rigid_body_set.get(component.rigidbody_handle) {
+                component.previous_position = *rigidbody.position();
+            }
+        }
+    }
+
+    pub fn tween_system(&mut self, delta: f32) {
+        let mut tweens = std::mem::take(&mut self.tweens);
+        tweens.retain_mut(|tween| !tween.advance(self, delta));
+
+        // NOTE: Tweens added by the targets or completion callbacks start next tick
+        tweens.append(&mut self.tweens);
+        self.tweens = tweens;
+    }
+
+    /// Counts down the timers of every enabled entity, disabled ones keep theirs paused.
+    pub fn timer_system(&mut self, delta: f32) {
+        const QUERY: Query = Query::new(components::TIMER);
+
+        let Self {
+            entities,
+            timer_container,
+            ..
+        } = self;
+
+        entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .for_each(|(entity, _bitset)| {
+                // SAFETY: Only entities with a TimerComponent match the query
+                unsafe { timer_container.get_unchecked_mut(entity) }.update(delta);
+            });
+    }
+
+    /*
+        NOTE: The scripts are taken out of the game while they run, since a behavior
+              gets the game mutably through its `BehaviorCtx`. Nothing it can do there
+              adds or removes scripts, the command buffer is only flushed afterwards.
+    */
+    pub fn script_system(&mut self, delta: f32) {
+        const QUERY: Query = Query::new(components::SCRIPT);
+
+        let entities = self
+            .entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .map(|(entity, _bitset)| entity)
+            .collect::<Vec<_>>();
+
+        let mut scripts = std::mem::take(&mut self.script_container);
+        let mut ctx = BehaviorCtx { game: self };
+
+        for entity in entities {
+            if let Some(script) = scripts.get_mut(entity) {
+                script.behavior.update(&mut ctx, entity, delta);
+            }
+        }
+
+        self.script_container = scripts;
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn homing_system(&mut self, delta: f32) {
+        const QUERY: Query = Query::new(components::HOMING | components::RIGIDBODY);
+
+        let steering = self
+            .entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .filter_map(|(entity, _bitset)| {
+                let homing = unsafe { self.homing_container.get_unchecked(entity) };
+                let position = self.position(entity)?;
+                let velocity = self.linvel(entity)?;
+
+                let target = homing
+                    .target
+                    .filter(|target| self.is_alive(*target) && self.is_enabled(*target))
+                    .or_else(|| {
+                        self.nearest_entity(position, Some(homing.targets))
+                            .map(|(target, _distance)| target)
+                            .filter(|target| *target != entity)
+                    });
+
+                let to_target = target
+                    .and_then(|target| self.entity_position(target))
+                    .map(|target_position| target_position - position);
+
+                let heading = velocity.y.atan2(velocity.x);
+                let heading = match to_target {
+                    // NOTE: A body at rest turns towards its target right away
+                    Some(to_target) if velocity.length_squared() < f32::EPSILON => {
+                        to_target.y.atan2(to_target.x)
+                    }
+
+                    Some(to_target) => {
+                        let max_turn = homing.turn_rate.max(0.0) * delta;
+                        let turn = (to_target.y.atan2(to_target.x) - heading + PI)
+                            .rem_euclid(2.0 * PI)
+                            - PI;
+
+                        heading + turn.clamp(-max_turn, max_turn)
+                    }
+
+                    None => heading,
+                };
+
+                Some((
+                    entity,
+                    target,
+                    vec2(heading.cos(), heading.sin()) * homing.speed,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        for (entity, target, velocity) in steering {
+            /*
+                SAFETY: `steering` was collected from entities with a HomingComponent
+            */
+            unsafe { self.homing_container.get_unchecked_mut(entity) }.target = target;
+            self.set_linvel(entity, velocity);
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn wind_system(&mut self, delta: f32) {
+        if self.wind == Vec2::ZERO {
+            return;
+        }
+
+        let wind = vector![self.wind.x, self.wind.y];
+
+        self.rigidbody_container
+            .iter()
+            .filter(|(entity, _rigidbody_component)| {
+                !self.entities[*entity].contains(components::DISABLED)
+            })
+            .for_each(|(entity, rigidbody_component)| {
+                let rigidbody = &mut self.rigid_body_set[rigidbody_component.rigidbody_handle];
+                if !rigidbody.is_dynamic() {
+                    return;
+                }
+
+                let drag = self.wind_drag.get(entity).copied().unwrap_or(1.0);
+
+                // NOTE: Scaled by mass so that, like gravity, wind accelerates everything alike
+                let impulse = wind * drag * rigidbody.mass() * delta;
+                rigidbody.apply_impulse(impulse, true);
+            });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn feet_sensor_system(&mut self) {
+        let grounded = self
+            .player_container
+            .iter()
+            .filter_map(|(entity, player)| Some((entity, player.feet_sensor?)))
+            .map(|(entity, sensor)| {
+                let on_ground = self
+                    .narrow_phase
+                    .intersections_with(sensor)
+                    .filter(|(_h1, _h2, intersecting)| *intersecting)
+                    .map(|(h1, h2, _intersecting)| if h1 == sensor { h2 } else { h1 })
+                    .any(|other| {
+                        // NOTE: Triggers and the player's own colliders aren't ground
+                        let solid = self
+                            .collider_set
+                            .get(other)
+                            .is_some_and(|collider| !collider.is_sensor());
+
+                        solid && self.collider_entity(other) != Some(entity)
+                    });
+
+                (entity, on_ground)
+            })
+            .collect::<Vec<_>>();
+
+        for (entity, on_ground) in grounded {
+            /*
+                SAFETY: `grounded` was collected from `player_container`
+            */
+            unsafe { self.player_container.get_unchecked_mut(entity) }.feet_grounded = on_ground;
+        }
+    }
+
+    /*
+        Squashes a player's sprite when it lands and stretches it when it jumps, then
+        eases it back with a tween. Only changes `TextureComponent::render_scale`.
+
+        NOTE: A jump is leaving the ground while moving up, walking off a ledge doesn't
+              count. Which also means a bounce off the ground squashes and stretches.
+    */
+    #[cfg(feature = "physics")]
+    pub fn squash_stretch_system(&mut self) {
+        let tuning = self.player_tuning;
+
+        let players = self
+            .player_container
+            .keys()
+            .filter(|entity| !self.entities[*entity].contains(components::DISABLED))
+            .collect::<Vec<_>>();
+
+        for entity in players {
+            let grounded = self.player_grounded(entity);
+            let rising = self.linvel(entity).is_some_and(|linvel| linvel.y < 0.0);
+
+            let player = &mut self.player_container[entity];
+            let was_grounded = std::mem::replace(&mut player.was_grounded, grounded);
+
+            let scale = match (was_grounded, grounded) {
+                (false, true) => tuning.land_squash,
+                (true, false) if rising => tuning.jump_stretch,
+                _ => continue,
+            };
+
+            if !self.texture_container.contains_key(entity) {
+                continue;
+            }
+
+            player.squash_generation = player.squash_generation.wrapping_add(1);
+            let generation = player.squash_generation;
+
+            let tween = Tween::new(
+                0.0,
+                1.0,
+                tuning.squash_duration,
+                move |game: &mut Game, t| {
+                    let current = game
+                        .player_container
+                        .get(entity)
+                        .is_some_and(|player| player.squash_generation == generation);
+
+                    if let (true, Some(texture)) = (current, game.texture_container.get_mut(entity))
+                    {
+                        texture.render_scale = scale.lerp(Vec2::ONE, t);
+                    }
+                },
+            );
+
+            self.add_tween(tween.with_easing(utils::Easing::QuadOut));
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn conveyor_system(&mut self) {
+        const QUERY: Query = Query::new(components::CONVEYOR | components::COLLIDER);
+
+        let touching = self
+            .entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .flat_map(|(conveyor, _bitset)| {
+                let handle =
+                    unsafe { self.collider_container.get_unchecked(conveyor) }.collider_handle();
+                let velocity =
+                    unsafe { self.conveyor_container.get_unchecked(conveyor) }.surface_velocity;
+
+                self.narrow_phase
+                    .contacts_with(handle)
+                    .filter(|pair| pair.has_any_active_contact)
+                    .filter_map(move |pair| {
+                        let other = if pair.collider1 == handle {
+                            pair.collider2
+                        } else {
+                            pair.collider1
+                        };
+
+                        let normal = pair.manifolds.first()?.data.normal;
+                        Some((other, normal, velocity))
+                    })
+            })
+            .filter_map(|(other, normal, velocity)| {
+                Some((self.collider_entity(other)?, normal, velocity))
+            })
+            .collect::<Vec<_>>();
+
+        touching
+            .into_iter()
+            .for_each(|(entity, normal, surface_velocity)| {
+                let rigidbody = match self.rigidbody_mut(entity) {
+                    Some(rigidbody) if rigidbody.is_dynamic() => rigidbody,
+                    _ => return,
+                };
+
+                // NOTE: The sign of the normal doesn't matter, only the axis along the belt
+                let tangent = vector![-normal.y, normal.x];
+                let belt_speed = tangent.dot(&vector![surface_velocity.x, surface_velocity.y]);
+
+                let linvel = *rigidbody.linvel();
+                let linvel = linvel + tangent * (belt_speed - tangent.dot(&linvel));
+                rigidbody.set_linvel(linvel, true);
+            });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn net_interp_system(&mut self, delta: f32) {
+        self.net_time += delta as f64;
+
+        self.net_interp_container
+            .iter()
+            .filter(|(entity, _interp)| !self.entities[*entity].contains(components::DISABLED))
+            .for_each(|(entity, interp)| {
+                /*
+                    SAFETY: NET_INTERP requires RIGIDBODY
+                */
+                let handle =
+                    unsafe { self.rigidbody_container.get_unchecked(entity) }.rigidbody_handle;
+                let (position, rotation) = interp.sample(self.net_time);
+
+                let rigidbody = &mut self.rigid_body_set[handle];
+                rigidbody.set_next_kinematic_translation(vector![position.x, position.y]);
+                rigidbody.set_next_kinematic_rotation(rotation);
+            });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn trigger_system(&mut self) {
+        const QUERY: Query = Query::new(components::TRIGGER | components::COLLIDER);
+
+        let triggers = self
+            .entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .map(|(entity, _bitset)| entity)
+            .collect::<Vec<_>>();
+
+        for trigger in triggers {
+            let handle =
+                unsafe { self.collider_container.get_unchecked(trigger) }.collider_handle();
+
+            let current = self.sensor_overlaps(handle).collect::<BTreeSet<_>>();
+
+            let previous = self
+                .trigger_overlaps
+                .insert(trigger, current)
+                .unwrap_or_default();
+            let current = &self.trigger_overlaps[trigger];
+
+            current.difference(&previous).for_each(|&entity| {
+                self.events
+                    .push(GameEvent::TriggerEnter { trigger, entity });
+            });
+
+            previous.difference(current).for_each(|&entity| {
+                self.events.push(GameEvent::TriggerExit { trigger, entity });
+            });
+        }
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn bounds_system(&mut self) {
+        let bounds = match self.world_bounds {
+            Some(bounds) => bounds,
+            None => return,
+        };
+
+        self.rigidbody_container
+            .iter()
+            .filter(|(entity, _rigidbody_component)| {
+                !self.entities[*entity].contains(components::DISABLED)
+            })
+            .for_each(|(entity, rigidbody_component)| {
+                let rigidbody = &self.rigid_body_set[rigidbody_component.rigidbody_handle];
+                let pos = rigidbody.translation();
+
+                if !bounds.contains(vec2(pos.x, pos.y)) {
+                    self.events.push(GameEvent::OutOfBounds { entity });
+                }
+            });
+    }
+
+    /// Despawns everything below `kill_plane_y`, except for players which respawn instead.
+    #[cfg(feature = "physics")]
+    pub fn kill_plane_system(&mut self) {
+        let kill_plane_y = match self.kill_plane_y {
+            Some(kill_plane_y) => kill_plane_y,
+            None => return,
+        };
+
+        let below = self
+            .rigidbody_container
+            .iter()
+            .filter(|(entity, rigidbody_component)| {
+                let rigidbody = &self.rigid_body_set[rigidbody_component.rigidbody_handle];
+
+                !self.entities[*entity].contains(components::DISABLED)
+                    && rigidbody.translation().y > kill_plane_y
+            })
+            .map(|(entity, _rigidbody_component)| entity)
+            .collect::<Vec<_>>();
+
+        below.into_iter().for_each(|entity| {
+            if self.player_container.contains_key(entity) {
+                self.respawn_player(entity);
+            } else {
+                self.commands.despawn(entity);
+            }
+        });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn respawn_system(&mut self) {
+        let mut to_respawn = vec![];
+
+        for event in &self.events {
+            match *event {
+                GameEvent::TriggerEnter { trigger, entity }
+                    if self.has_flag(trigger, components::CHECKPOINT)
+                        && self.player_container.contains_key(entity) =>
+                {
+                    /*
+                        SAFETY: Checkpoints are triggers, which always have a ColliderComponent
+                    */
+                    let handle =
+                        unsafe { self.collider_container.get_unchecked(trigger) }.collider_handle();
+                    let pos = self.collider_set[handle].translation();
+
+                    self.player_container[entity].respawn_point = vec2(pos.x, pos.y);
+                }
+
+                // NOTE: A player can both die and leave the bounds in one tick, which costs one life
+                GameEvent::Died { entity } | GameEvent::OutOfBounds { entity }
+                    if self.player_container.contains_key(entity)
+                        && !to_respawn.contains(&entity) =>
+                {
+                    to_respawn.push(entity);
+                }
+
+                _ => {}
+            }
+        }
+
+        self.resources.lives = self.resources.lives.saturating_sub(to_respawn.len() as u32);
+
+        to_respawn
+            .into_iter()
+            .for_each(|entity| self.respawn_player(entity));
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn trail_system(&mut self) {
+        const QUERY: Query = Query::new(components::TRAIL | components::RIGIDBODY);
+
+        self.entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .for_each(|(entity, _bitset)| {
+                /*
+                    SAFETY: The query guarantees both components are present
+                */
+                let trail = unsafe { self.trail_container.get_unchecked_mut(entity) };
+                let handle =
+                    unsafe { self.rigidbody_container.get_unchecked(entity) }.rigidbody_handle;
+                let pos = self.rigid_body_set[handle].translation();
+
+                // NOTE: Popping before pushing keeps the deque within its initial allocation
+                while trail.positions.len() >= trail.max.max(1) {
+                    trail.positions.pop_front();
+                }
+
+                if trail.max > 0 {
+                    trail.positions.push_back(vec2(pos.x, pos.y));
+                }
+            });
+    }
+
+    fn profiled(&mut self, name: &'static str, system: impl FnOnce(&mut Self)) {
+        let start = self.profiler.start();
+        system(self);
+        self.profiler.stop(name, start);
+    }
+
+    fn run_system(&mut self, name: &'static str, system: impl FnOnce(&mut Self)) {
+        self.profiled(name, |game| {
+            system(game);
+            game.flush_commands();
+        });
+    }
+
+    /// Rolling averages of how long each system took, while profiling is enabled.
+    pub fn timings(&self) -> &Timings {
+        self.profiler.timings()
+    }
+
+    /*
+        NOTE: The camera only moves once the target, pushed ahead along its velocity,
+              leaves the dead zone, and then only far enough to bring it back to the
+              edge. Small moves like idling or landing leave the view alone.
+    */
+    pub fn camera_follow_system(&mut self, delta: f32) {
+        let Some(entity) = self.camera_target() else {
+            return;
+        };
+
+        let Some(position) = self.entity_position(entity) else {
+            return;
+        };
+
+        let tuning = self.camera_tuning;
+
+        #[cfg(feature = "physics")]
+        let velocity = self.linvel(entity).unwrap_or_default();
+        #[cfg(not(feature = "physics"))]
+        let velocity = Vec2::ZERO;
+
+        let look_ahead = (velocity * tuning.look_ahead).clamp_length_max(tuning.max_look_ahead);
+        let focus = position + look_ahead;
+
+        let dead_zone = tuning.dead_zone.abs();
+        let camera = self.camera.target;
+        let goal = focus.clamp(camera - dead_zone, camera + dead_zone);
+        let goal = camera + (focus - goal);
+
+        let t = (tuning.follow_speed * delta).min(Vec2::ONE);
+
+        self.camera.target.x = lerp(camera.x, goal.x, t.x);
+        self.camera.target.y = lerp(camera.y, goal.y, t.y);
+    }
+
+    pub fn run_logic_systems(&mut self, delta: f32) {
+        let delta = delta * self.time_scale;
+
+        if delta > 0.0 {
+            self.events.drain(..self.stale_events);
+
+            self.resources.elapsed += delta as f64;
+            #[cfg(feature = "physics")]
+            self.store_previous_positions();
+
+            self.profiled("deferred_spawns", Self::begin_spawn_frame);
+            self.run_system("tween", |game| game.tween_system(delta));
+            self.run_system("timer", |game| game.timer_system(delta));
+            self.run_system("script", |game| game.script_system(delta));
+            #[cfg(feature = "physics")]
+            {
+                self.run_system("player_movement", |game| game.player_movement_system(delta));
+                self.run_system("drag_zone", |game| game.drag_zone_system(delta));
+                self.run_system("homing", |game| game.homing_system(delta));
+                self.run_system("wind", |game| game.wind_system(delta));
+                self.run_system("net_interp", |game| game.net_interp_system(delta));
+                self.run_system("physics", |game| game.physics_system(delta));
+                self.run_system("feet_sensor", Self::feet_sensor_system);
+                self.run_system("squash_stretch", Self::squash_stretch_system);
+                self.run_system("conveyor", Self::conveyor_system);
+                self.run_system("trigger", Self::trigger_system);
+                self.run_system("trail", Self::trail_system);
+                self.run_system("bounds", Self::bounds_system);
+                self.run_system("kill_plane", Self::kill_plane_system);
+                self.run_system("respawn", Self::respawn_system);
+            }
+            self.run_system("camera_follow", |game| game.camera_follow_system(delta));
+            self.profiled("despawns", Self::flush_despawns);
+
+            self.stale_events = self.events.len();
+
+            #[cfg(debug_assertions)]
+            if let Err(violations) = self.validate() {
+                panic!("ECS invariants violated:\n{}", violations.join("\n"));
+            }
+        }
+    }
+}
+
+// Rendering Systems
+impl Game {
+    pub fn render_stats(&self) -> RenderStats {
+        self.render_stats.get()
+    }
+
+    #[inline]
+    fn count_rendering(&self, count: impl FnOnce(&mut RenderStats)) {
+        let mut stats = self.render_stats.get();
+        count(&mut stats);
+        self.render_stats.set(stats);
+    }
+
+    #[inline]
+    fn count_draw_calls(&self, draw_calls: usize) {
+        self.count_rendering(|stats| stats.draw_calls += draw_calls);
+    }
+
+    /// The world space area the camera covers of a screen of `screen_size` pixels.
+    pub fn visible_world_rect(&self, screen_size: Vec2) -> Rect {
+        let a = screen_to_world(&self.camera, vec2(0.0, 0.0), screen_size);
+        let b = screen_to_world(&self.camera, screen_size, screen_size);
+
+        // NOTE: The negative zoom mirrors the axes, so the corners can come out swapped
+        let min = a.min(b);
+        let max = a.max(b);
+
+        Rect::new(min.x, min.y, max.x - min.x, max.y - min.y)
+    }
+
+    pub fn render_parallax_system(&self, renderer: &mut dyn Renderer) {
+        let view = self.visible_world_rect(renderer.screen_size());
+
+        for layer in &self.parallax_layers {
+            for position in layer.tiles(view, self.camera.target) {
+                renderer.draw_texture(layer.texture, position, WHITE, DrawTextureParams::default());
+                self.count_draw_calls(1);
+            }
+        }
+    }
+
+    pub fn render_tilemap_system(&self, renderer: &mut dyn Renderer) {
+        let view = self.visible_world_rect(renderer.screen_size());
+
+        self.tilemap_container
+            .iter()
+            .filter(|(entity, _tilemap)| !self.entities[*entity].contains(components::DISABLED))
+            .for_each(|(_entity, tilemap)| {
+                let size = tilemap.tile_size;
+                let columns = ((tilemap.tileset.width() / size) as u16).max(1);
+
+                let (xs, ys) = tilemap.visible_range(view);
+                for y in ys {
+                    for x in xs.clone() {
+                        let Some(tile) = tilemap.tiles[y * tilemap.width + x] else {
+                            continue;
+                        };
+
+                        let source = Rect::new(
+                            (tile % columns) as f32 * size,
+                            (tile / columns) as f32 * size,
+                            size,
+                            size,
+                        );
+
+                        renderer.draw_texture(
+                            tilemap.tileset,
+                            tilemap.origin + vec2(x as f32, y as f32) * size,
+                            WHITE,
+                            DrawTextureParams {
+                                dest_size: Some(vec2(size, size)),
+                                source: Some(source),
+
+                                ..Default::default()
+                            },
+                        );
+                        self.count_draw_calls(1);
+                    }
+                }
+            });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn render_sprites_system(&self, renderer: &mut dyn Renderer) {
+        let screen_size = renderer.screen_size();
+        let view = self.visible_world_rect(screen_size);
+
+        self.query2::<TextureComponent, RigidbodyComponent>()
+            .filter(|(entity, _tex, _rigidbody_component)| {
+                !self.has_flag(*entity, components::SCREEN_SPACE)
+            })
+            .for_each(|(entity, tex, _rigidbody_component)| {
+                let (pos, rotation) = self
+                    .interpolated_position(entity, self.render_alpha)
+                    .unwrap();
+
+                // NOTE: Flash while dashing
+                let dashing = self
+                    .player_container
+                    .get(entity)
+                    .is_some_and(PlayerComponent::is_dashing);
+
+                let size = tex.draw_size();
+
+                // NOTE: Half the diagonal covers the sprite at any rotation
+                let radius = size.length() / 2.0;
+                let bounds = Rect::new(pos.x - radius, pos.y - radius, radius * 2.0, radius * 2.0);
+                if !view.overlaps(&bounds) {
+                    self.count_rendering(|stats| stats.sprites_culled += 1);
+                    return;
+                }
+
+                let mut corner = pos - size / 2.0;
+                if self.pixel_snap {
+                    corner = snap_to_pixel(corner, &self.camera, screen_size);
+                }
+
+                renderer.draw_texture(
+                    tex.texture,
+                    corner,
+                    if dashing { WHITE } else { tex.color },
+                    DrawTextureParams {
+                        dest_size: Some(size),
+                        rotation: tex.draw_rotation(rotation),
+
+                        ..Default::default()
+                    },
+                );
+                self.count_rendering(|stats| {
+                    stats.draw_calls += 1;
+                    stats.sprites_drawn += 1;
+                });
+            });
+    }
+
+    /// Draws screen space entities, so it runs after `set_default_camera`.
+    pub fn render_screen_space_system(&self, renderer: &mut dyn Renderer) {
+        self.query2::<TextureComponent, ScreenSpaceComponent>()
+            .for_each(|(_entity, tex, screen_space)| {
+                let rect = screen_space.rect(tex.draw_size());
+
+                renderer.draw_texture(
+                    tex.texture,
+                    rect.point(),
+                    tex.color,
+                    DrawTextureParams {
+                        dest_size: Some(rect.size()),
+
+                        ..Default::default()
+                    },
+                );
+                self.count_rendering(|stats| {
+                    stats.draw_calls += 1;
+                    stats.sprites_drawn += 1;
+                });
+            });
+    }
+
+    pub fn render_trails_system(&self, renderer: &mut dyn Renderer) {
+        const QUERY: Query = Query::new(components::TRAIL);
+        const THICKNESS: f32 = 2.0;
+
+        self.entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .for_each(|(entity, _bitset)| {
+                let trail = unsafe { self.trail_container.get_unchecked(entity) };
+                let color = self
+                    .texture_container
+                    .get(entity)
+                    .map_or(WHITE, |tex| tex.color);
+
+                let segments = trail.positions.len().saturating_sub(1);
+
+                trail
+                    .positions
+                    .iter()
+                    .zip(trail.positions.iter().skip(1))
+                    .enumerate()
+                    .for_each(|(i, (from, to))| {
+                        // Older segments are fainter, down to `1 / segments` for the oldest
+                        let alpha = (i + 1) as f32 / segments as f32;
+
+                        renderer.draw_line(
+                            *from,
+                            *to,
+                            THICKNESS,
+                            Color {
+                                a: color.a * alpha,
+                                ..color
+                            },
+                        );
+                        self.count_draw_calls(1);
+                    });
+            });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn render_contacts_system(&self, renderer: &mut dyn Renderer) {
+        const POINT_RADIUS: f32 = 2.0;
+        const NORMAL_LENGTH: f32 = 10.0;
+
+        self.narrow_phase
+            .contact_pairs()
+            .filter(|pair| pair.has_any_active_contact)
+            .flat_map(|pair| pair.manifolds.iter())
+            .for_each(|manifold| {
+                // NOTE: Both the normal and the solver contacts are already in world space
+                let normal = manifold.data.normal;
+
+                manifold.data.solver_contacts.iter().for_each(|contact| {
+                    let point = vec2(contact.point.x, contact.point.y);
+
+                    renderer.draw_circle(point, POINT_RADIUS, YELLOW);
+                    renderer.draw_line(
+                        point,
+                        point + vec2(normal.x, normal.y) * NORMAL_LENGTH,
+                        1.0,
+                        ORANGE,
+                    );
+                    self.count_draw_calls(2);
+                });
+            });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn render_velocities_system(&self, renderer: &mut dyn Renderer) {
+        const QUERY: Query = Query::new(components::RIGIDBODY);
+
+        // Seconds of travel shown by an arrow, so faster bodies get longer arrows
+        const VELOCITY_SCALE: f32 = 0.1;
+        const MAX_LENGTH: f32 = 60.0;
+        const HEAD_LENGTH: f32 = 4.0;
+
+        self.entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .for_each(|(entity, bitset)| {
+                let rigidbody = &self.rigid_body_set
+                    [unsafe { self.rigidbody_container.get_unchecked(entity) }.rigidbody_handle];
+
+                if !rigidbody.is_dynamic() {
+                    return;
+                }
+
+                // NOTE: Starts where the sprite is drawn, which lags behind the body
+                let (from, _rotation) = self
+                    .interpolated_position(entity, self.render_alpha)
+                    .unwrap();
+                let linvel = rigidbody.linvel();
+
+                let arrow =
+                    (vec2(linvel.x, linvel.y) * VELOCITY_SCALE).clamp_length_max(MAX_LENGTH);
+
+                if arrow.length_squared() < f32::EPSILON {
+                    return;
+                }
+
+                let to = from + arrow;
+                let color = if bitset.contains(components::PLAYER) {
+                    SKYBLUE
+                } else {
+                    GREEN
+                };
+
+                let back = -arrow.normalize() * HEAD_LENGTH;
+                let side = back.perp() * 0.5;
+
+                renderer.draw_line(from, to, 1.0, color);
+                renderer.draw_line(to, to + back + side, 1.0, color);
+                renderer.draw_line(to, to + back - side, 1.0, color);
+                self.count_draw_calls(3);
+            });
+    }
+
+    #[cfg(feature = "physics")]
+    pub fn render_fixed_colliders(&self, renderer: &mut dyn Renderer) {
+        const QUERY: Query = Query::new(components::FIXED_COLLIDER | components::RIGIDBODY);
+
+        self.entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                QUERY.is_subset_of(bitset) && !bitset.contains(components::DISABLED)
+            })
+            .flat_map(|(entity, _bitset)| {
+                unsafe { self.collider_container.get_unchecked(entity) }
+                    .collider_handles
+                    .iter()
+            })
+            .for_each(|handle| {
+                let collider = self.collider_set.get(*handle).unwrap();
+
+                renderer.draw_rect_lines(aabb_rect(&collider.compute_aabb()), 0.0, RED);
+                self.count_draw_calls(1);
+            });
+    }
+
+    /// Outlines every collider in the color of its collision group memberships, see
+    /// `group_color`. Disabled entities have none, so they show up gray.
+    #[cfg(feature = "physics")]
+    pub fn render_collision_groups_system(&self, renderer: &mut dyn Renderer) {
+        for (_handle, collider) in self.collider_set.iter() {
+            let color = group_color(collider.collision_groups().memberships);
+
+            renderer.draw_rect_lines(aabb_rect(&collider.compute_aabb()), 1.0, color);
+            self.count_draw_calls(1);
+        }
+    }
+
+    /// Outlines the AABB the broad phase sees of every collider of every enabled entity.
+    #[cfg(feature = "physics")]
+    pub fn render_broadphase_system(&self, renderer: &mut dyn Renderer) {
+        self.entities
+            .iter()
+            .filter(|(_entity, bitset)| {
+                bitset.contains(components::COLLIDER) && !bitset.contains(components::DISABLED)
+            })
+            .flat_map(|(entity, _bitset)| {
+                unsafe { self.collider_container.get_unchecked(entity) }
+                    .collider_handles
+                    .iter()
+            })
+            .filter_map(|handle| self.collider_set.get(*handle))
+            .for_each(|collider| {
+                renderer.draw_rect_lines(aabb_rect(&collider.compute_aabb()), 1.0, ORANGE);
+                self.count_draw_calls(1);
+            });
+    }
+
+    /// Screen space key for `render_collision_groups_system`, one row per distinct
+    /// set of memberships in the bottom left corner.
+    #[cfg(feature = "physics")]
+    pub fn render_collision_group_legend(&self, renderer: &mut dyn Renderer) {
+        if !self.is_render_pass_enabled(RenderPass::CollisionGroups) {
+            return;
+        }
+
+        const FONT_SIZE: f32 = 16.0;
+        const SWATCH: f32 = 10.0;
+
+        let mut counts: BTreeMap<u32, usize> = BTreeMap::new();
+        for (_handle, collider) in self.collider_set.iter() {
+            *counts
+                .entry(collider.collision_groups().memberships)
+                .or_default() += 1;
+        }
+
+        let mut y = renderer.screen_size().y - FONT_SIZE * counts.len() as f32;
+        for (memberships, count) in counts {
+            let groups = match memberships {
+                0 => "none".to_owned(),
+                u32::MAX => "all".to_owned(),
+                _ => (0..32)
+                    .filter(|group| memberships & (1 << group) != 0)
+                    .map(|group| group.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+            };
+
+            renderer.draw_rect(
+                Rect::new(4.0, y - SWATCH, SWATCH, SWATCH),
+                group_color(memberships),
+            );
+            renderer.draw_text(
+                &format!("groups {groups} ({count})"),
+                vec2(8.0 + SWATCH, y),
+                FONT_SIZE,
+                WHITE,
+            );
+
+            y += FONT_SIZE;
+        }
+    }
+
+    /// Selects the entity `step` places away from the current selection, ordered
+    /// by label and then by key, wrapping around at both ends.
+    #[cfg(feature = "editor")]
+    pub fn cycle_selection(&mut self, step: isize) {
+        let mut entities: Vec<Entity> = self.entities.keys().collect();
+        if entities.is_empty() {
+            self.selected = None;
+            return;
+        }
+
+        entities.sort_by_key(|entity| (self.label_container.get(*entity).copied(), *entity));
+
+        let len = entities.len() as isize;
+        let next = match self
+            .selected
+            .and_then(|s| entities.iter().position(|e| *e == s))
+        {
+            Some(index) => (index as isize + step).rem_euclid(len),
+
+            // NOTE: Without a selection, Tab starts at the first entity and Shift+Tab at the last
+            None if step >= 0 => 0,
+            None => len - 1,
+        };
+
+        self.selected = Some(entities[next as usize]);
+    }
+
+    /// World space size of the entity's collider, or of its sprite without one.
+    #[cfg(feature = "editor")]
+    fn entity_extents(&self, entity: Entity) -> Option<Vec2> {
+        #[cfg(feature = "physics")]
+        {
+            use rapier2d::parry::bounding_volume::BoundingVolume;
+
+            // NOTE: Compound entities are framed as a whole
+            let aabb = self
+                .colliders_of(entity)
+                .iter()
+                .filter_map(|handle| self.collider_set.get(*handle))
+                .map(|collider| collider.compute_aabb())
+                .reduce(|a, b| a.merged(&b));
+
+            if let Some(aabb) = aabb {
+                let extents = aabb.extents();
+                return Some(vec2(extents.x, extents.y));
+            }
+        }
+
+        self.texture_container
+            .get(entity)
+            .map(|texture| texture.size)
+    }
+
+    /// Zooms so that the selected entity fills `FRAME_FRACTION` of the view.
+    /// The camera itself is moved onto the selection by `editor_input_system`.
+    #[cfg(feature = "editor")]
+    pub fn frame_selected(&mut self) {
+        let Some(extents) = self.selected.and_then(|entity| self.entity_extents(entity)) else {
+            return;
+        };
+
+        /*
+            NOTE: The view spans `2.0 / |zoom|` world units horizontally and the
+                  y zoom is scaled by the aspect ratio in `run_rendering_systems`.
+        */
+        let aspect = screen_width() / screen_height();
+        let fit_x = 2.0 * FRAME_FRACTION / extents.x.max(f32::EPSILON);
+        let fit_y = 2.0 * FRAME_FRACTION / (extents.y.max(f32::EPSILON) * aspect);
+
+        let zoom = fit_x.min(fit_y).min(3.0);
+        self.target_zoom = if self.target_zoom > 0.0 { zoom } else { -zoom };
+    }
+
+    #[cfg(feature = "editor")]
+    pub fn editor_input_system(&mut self, delta: f32) {
+        if self.selected.is_some_and(|entity| !self.is_alive(entity)) {
+            self.selected = None;
+        }
+
+        if let Some(action) = self.rebinding {
+            if let Some(key_code) = get_last_key_pressed() {
+                self.set_binding(action, key_code);
+                self.rebinding = None;
+            }
+
+            // NOTE: The key being bound should not also trigger its old action
+            return;
+        }
+
+        // NOTE: egui uses Tab itself to move focus between widgets
+        let keyboard_free = !self.egui_wants_keyboard && !self.console.open;
+
+        if keyboard_free && is_key_pressed(self.keys[&Actions::SelectNext]) {
+            let backwards = is_key_down(KeyCode::LeftShift) || is_key_down(KeyCode::RightShift);
+            self.cycle_selection(if backwards { -1 } else { 1 });
+        }
+
+        if keyboard_free && is_key_pressed(self.keys[&Actions::FrameSelected]) {
+            self.frame_selected();
+        }
+
+        if keyboard_free && is_key_pressed(KeyCode::Escape) {
+            self.measurement = Measurement::None;
+        }
+
+        if self.measure_tool
+            && !self.egui_wants_pointer
+            && is_mouse_button_pressed(MouseButton::Left)
+        {
+            let screen_size = vec2(screen_width(), screen_height());
+            let point = screen_to_world(&self.camera, mouse_position().into(), screen_size);
+            self.measure_click(point);
+        }
+
+        // NOTE: Overrides the player follow camera for as long as something is selected
+        if let Some(position) = self
+            .selected
+            .and_then(|entity| self.entity_position(entity))
+        {
+            let t = (delta * ZOOM_SMOOTHING).min(1.0);
+            self.camera.target = self.camera.target.lerp(position, t);
+        }
+    }
+
+    /// Feeds a click at the world position `point` to the measure tool.
+    #[cfg(feature = "editor")]
+    pub fn measure_click(&mut self, point: Vec2) {
+        self.measurement = self.measurement.click(point);
+    }
+
+    #[cfg(feature = "editor")]
+    pub fn measurement(&self) -> Measurement {
+        self.measurement
+    }
+
+    /// Whether Escape would clear a measurement rather than do what it is bound to.
+    #[cfg(feature = "editor")]
+    pub fn is_measuring(&self) -> bool {
+        self.measurement != Measurement::None
+    }
+
+    /*
+        The measurement in screen space, so the line and label stay the same thickness
+        and size at any zoom. The label has both the distance in the world and how long
+        it is on screen at the current zoom.
+    */
+    #[cfg(feature = "editor")]
+    pub fn render_measurement_system(&self, renderer: &mut dyn Renderer) {
+        const FONT_SIZE: f32 = 16.0;
+        const THICKNESS: f32 = 2.0;
+        const COLOR: Color = YELLOW;
+
+        let screen_size = renderer.screen_size();
+
+        match self.measurement {
+            Measurement::None => {}
+
+            Measurement::Started(start) => {
+                let start = world_to_screen(&self.camera, start, screen_size);
+                renderer.draw_circle(start, THICKNESS * 2.0, COLOR);
+            }
+
+            Measurement::Done(start, end) => {
+                let (from, to) = (
+                    world_to_screen(&self.camera, start, screen_size),
+                    world_to_screen(&self.camera, end, screen_size),
+                );
+
+                renderer.draw_line(from, to, THICKNESS, COLOR);
+                renderer.draw_circle(from, THICKNESS * 2.0, COLOR);
+                renderer.draw_circle(to, THICKNESS * 2.0, COLOR);
+
+                let text = format!(
+                    "{:.1} world units ({:.0} px on screen)",
+                    start.distance(end),
+                    from.distance(to)
+                );
+                let size = renderer.measure_text(&text, FONT_SIZE);
+                let midpoint = (from + to) / 2.0;
+
+                renderer.draw_text(
+                    &text,
+                    vec2(midpoint.x - size.x / 2.0, midpoint.y - FONT_SIZE / 2.0),
+                    FONT_SIZE,
+                    COLOR,
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "editor")]
+    pub fn render_gui_system(&mut self) {
+        egui_macroquad::ui(|egui_ctx| {
+            self.egui_wants_keyboard = egui_ctx.wants_keyboard_input();
+            self.egui_wants_pointer = egui_ctx.wants_pointer_input();
+
+            let r = 8.0;
+
+            egui_ctx.set_style(egui::Style {
+                visuals: egui::Visuals {
+                    dark_mode: true,
+                    window_rounding: egui::Rounding {
+                        nw: r,
+                        ne: r,
+                        sw: r,
+                        se: r,
+                    },
+                    ..Default::default()
+                },
+
+                ..Default::default()
+            });
+
+            egui::Window::new("egui ❤ macroquad").show(egui_ctx, |ui| {
+                ui.heading("Camera");
+
+                ui.vertical(|ui| {
+                    ui.label("pos");
+                    ui.horizontal(|ui| {
+                        ui.label("x:");
+                        ui.add(egui::DragValue::new(&mut self.camera.target.x));
+
+                        ui.label("y:");
+                        ui.add(egui::DragValue::new(&mut self.camera.target.y));
+                    });
+
+                    ui.label("zoom");
+                    ui.horizontal(|ui| {
+                        ui.add(egui::Slider::new(&mut self.target_zoom, -3.0..=3.0));
+                    });
+
+                    ui.checkbox(&mut self.pixel_snap, "pixel snap");
+
+                    let tuning = &mut self.camera_tuning;
+
+                    ui.label("dead zone");
+                    ui.horizontal(|ui| {
+                        ui.label("x:");
+                        ui.add(
+                            egui::DragValue::new(&mut tuning.dead_zone.x).clamp_range(0.0..=500.0),
+                        );
+
+                        ui.label("y:");
+                        ui.add(
+                            egui::DragValue::new(&mut tuning.dead_zone.y).clamp_range(0.0..=500.0),
+                        );
+                    });
+
+                    ui.label("look ahead");
+                    ui.horizontal(|ui| {
+                        ui.label("seconds:");
+                        ui.add(
+                            egui::DragValue::new(&mut tuning.look_ahead)
+                                .speed(0.01)
+                                .clamp_range(0.0..=2.0),
+                        );
+
+                        ui.label("max:");
+                        ui.add(
+                            egui::DragValue::new(&mut tuning.max_look_ahead)
+                                .clamp_range(0.0..=1_000.0),
+                        );
+                    });
+
+                    ui.horizontal(|ui| {
+                        let color = &mut self.background_color;
+                        let mut rgb = [color.r, color.g, color.b];
+
+                        ui.label("background");
+                        if ui.color_edit_button_rgb(&mut rgb).changed() {
+                            [color.r, color.g, color.b] = rgb;
+                        }
+                    });
+                });
+
+                ui.heading("Simulation");
+
+                ui.vertical(|ui| {
+                    ui.label("time scale");
+                    ui.add(egui::Slider::new(
+                        &mut self.time_scale,
+                        0.0..=MAX_TIME_SCALE,
+                    ));
+                });
+
+                ui.vertical(|ui| {
+                    ui.label("wind");
+                    ui.horizontal(|ui| {
+                        ui.label("x:");
+                        ui.add(egui::DragValue::new(&mut self.wind.x));
+
+                        ui.label("y:");
+                        ui.add(egui::DragValue::new(&mut self.wind.y));
+                    });
+                });
+
+                #[cfg(feature = "physics")]
+                ui.vertical(|ui| {
+                    ui.label("solver iterations");
+                    ui.horizontal(|ui| {
+                        let params = &mut self.integration_parameters;
+
+                        ui.label("velocity:");
+                        ui.add(
+                            egui::DragValue::new(&mut params.max_velocity_iterations)
+                                .clamp_range(1..=64),
+                        );
+
+                        ui.label("friction:");
+                        ui.add(
+                            egui::DragValue::new(&mut params.max_velocity_friction_iterations)
+                                .clamp_range(1..=64),
+                        );
+
+                        ui.label("stabilization:");
+                        ui.add(
+                            egui::DragValue::new(&mut params.max_stabilization_iterations)
+                                .clamp_range(1..=64),
+                        );
+                    });
+                });
+
+                #[cfg(feature = "physics")]
+                ui.vertical(|ui| {
+                    ui.label("spawned material");
+                    ui.horizontal(|ui| {
+                        let material = &mut self.default_material;
+
+                        ui.label("restitution:");
+                        ui.add(
+                            egui::DragValue::new(&mut material.restitution)
+                                .speed(0.01)
+                                .clamp_range(0.0..=1.0),
+                        );
+
+                        ui.label("friction:");
+                        ui.add(
+                            egui::DragValue::new(&mut material.friction)
+                                .speed(0.01)
+                                .clamp_range(0.0..=f32::MAX),
+                        );
+                    });
+                });
+
+                ui.heading("Selection");
+
+                ui.horizontal(|ui| match self.selected {
+                    Some(entity) => {
+                        ui.label(self.label_container.get(entity).copied().unwrap_or("?"));
+                        if ui.button("deselect").clicked() {
+                            self.selected = None;
+                        }
+                    }
+
+                    None => {
+                        ui.label("none (Tab / Shift+Tab to cycle)");
+                    }
+                });
+
+                ui.heading("Tools");
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .checkbox(&mut self.measure_tool, "measure distance")
+                        .changed()
+                    {
+                        self.measurement = Measurement::None;
+                    }
+
+                    if self.measure_tool {
+                        ui.label("click twice, a third click or Escape clears");
+                    }
+                });
+
+                ui.heading("Settings");
+
+                ui.vertical(|ui| {
+                    let settings = &mut self.settings;
+
+                    ui.label("master volume");
+                    ui.add(egui::Slider::new(&mut settings.master_volume, 0.0..=1.0));
+
+                    ui.label("music volume");
+                    ui.add(egui::Slider::new(&mut settings.music_volume, 0.0..=1.0));
+
+                    ui.checkbox(&mut settings.fullscreen, "fullscreen (on restart)");
+
+                    if ui.button("save").clicked() {
+                        if let Err(err) = settings.save(SETTINGS_PATH) {
+                            eprintln!("Could not save settings: {err}");
+                            self.toasts
+                                .push(format!("Could not save settings: {err}"), ERROR_TOAST_TIME);
+                        }
+                    }
+                });
+
+                ui.heading("Bindings");
+
+                egui::Grid::new("bindings").show(ui, |ui| {
+                    for &action in Actions::all() {
+                        ui.label(action.label());
+
+                        let text = match self.rebinding {
+                            Some(rebinding) if rebinding == action => "press a key...".to_owned(),
+                            _ => config::keycode_name(self.keys[&action]),
+                        };
+
+                        if ui.button(text).clicked() {
+                            self.rebinding = Some(action);
+                        }
+
+                        ui.end_row();
+                    }
+                });
+
+                ui.heading("Render passes");
+
+                ui.vertical(|ui| {
+                    let mut swap = None;
+
+                    for (i, pass) in self.render_layers.iter().enumerate() {
+                        ui.horizontal(|ui| {
+                            if ui.add_enabled(i > 0, egui::Button::new("^")).clicked() {
+                                swap = Some(i);
+                            }
+                            ui.label(pass.name());
+                        });
+                    }
+
+                    if let Some(i) = swap {
+                        self.render_layers.swap(i - 1, i);
+                    }
+
+                    for pass in RenderPass::ALL {
+                        let mut enabled = self.is_render_pass_enabled(pass);
+                        if ui.checkbox(&mut enabled, pass.name()).changed() {
+                            self.set_render_pass_enabled(pass, enabled);
+                        }
+                    }
+                });
+
+                ui.heading("Debug");
+
+                ui.vertical(|ui| {
+                    ui.checkbox(&mut self.profiler.enabled, "profiler");
+
+                    let recorder = &mut self.recorder;
+                    ui.horizontal(|ui| {
+                        ui.label("record every");
+                        ui.add(egui::Slider::new(&mut recorder.interval, 1..=30));
+                        ui.label("frames");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("for up to");
+                        ui.add(egui::Slider::new(&mut recorder.duration, 0.5..=30.0));
+                        ui.label("seconds");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("and up to");
+                        ui.add(egui::Slider::new(&mut recorder.max_frames, 1..=240));
+                        ui.label("frames");
+                    });
+                    ui.horizontal(|ui| {
+                        ui.label("downsample");
+                        ui.add(egui::Slider::new(&mut recorder.downsample, 1..=8));
+                    });
+
+                    for timing in self.profiler.timings().iter() {
+                        ui.label(format!(
+                            "{}: {:.3} ms",
+                            timing.name,
+                            timing.average.as_secs_f64() * 1_000.0
+                        ));
+                    }
+                });
+            });
+        });
+
+        egui_macroquad::draw();
+    }
+
+    pub fn render_pass(&self, pass: RenderPass, renderer: &mut dyn Renderer) {
+        match pass {
+            RenderPass::Parallax => self.render_parallax_system(renderer),
+            RenderPass::Tilemap => self.render_tilemap_system(renderer),
+            #[cfg(feature = "physics")]
+            RenderPass::FixedColliders => self.render_fixed_colliders(renderer),
+            RenderPass::Trails => self.render_trails_system(renderer),
+            #[cfg(feature = "physics")]
+            RenderPass::Sprites => self.render_sprites_system(renderer),
+            #[cfg(feature = "physics")]
+            RenderPass::Contacts => self.render_contacts_system(renderer),
+            #[cfg(feature = "physics")]
+            RenderPass::Velocities => self.render_velocities_system(renderer),
+            #[cfg(feature = "physics")]
+            RenderPass::CollisionGroups => self.render_collision_groups_system(renderer),
+            #[cfg(feature = "physics")]
+            RenderPass::Broadphase => self.render_broadphase_system(renderer),
+
+            // NOTE: These only draw rigidbodies and colliders
+            #[cfg(not(feature = "physics"))]
+            RenderPass::FixedColliders
+            | RenderPass::Sprites
+            | RenderPass::Contacts
+            | RenderPass::Velocities
+            | RenderPass::CollisionGroups
+            | RenderPass::Broadphase => {}
+        }
+    }
+
+    /// Draws every enabled render pass, in the order of `render_layers`.
+    pub fn render_world(&mut self, renderer: &mut dyn Renderer) {
+        for i in 0..self.render_layers.len() {
+            let pass = self.render_layers[i];
+            self.profiled(pass.name(), |game| game.render_pass(pass, renderer));
+        }
+    }
+
+    pub fn set_pixel_snap(&mut self, pixel_snap: bool) {
+        self.pixel_snap = pixel_snap;
+    }
+
+    pub fn is_render_pass_enabled(&self, pass: RenderPass) -> bool {
+        self.render_layers.contains(&pass)
+    }
+
+    /// Enabling a pass draws it on top of the others, disabling one removes it from the list.
+    pub fn set_render_pass_enabled(&mut self, pass: RenderPass, enabled: bool) {
+        match (enabled, self.is_render_pass_enabled(pass)) {
+            (true, false) => self.render_layers.push(pass),
+            (false, true) => self.render_layers.retain(|layer| *layer != pass),
+            _ => {}
+        }
+
+        // NOTE: Keep the persisted debug toggles in sync
+        match pass {
+            RenderPass::Contacts => self.settings.show_contacts = enabled,
+            RenderPass::Velocities => self.settings.show_velocities = enabled,
+            _ => {}
+        }
+    }
+
+    pub fn run_rendering_systems(&mut self, delta: f32, alpha: f32) {
+        self.render_alpha = alpha;
+        self.render_stats.set(RenderStats::default());
+
+        clear_background(self.background_color);
+
+        self.console_system();
+        self.debug_keys_system();
+        self.toast_system(delta);
+
+        #[cfg(feature = "editor")]
+        self.editor_input_system(delta);
+
+        self.zoom = lerp(
+            self.zoom,
+            self.target_zoom,
+            (delta * ZOOM_SMOOTHING).min(1.0),
+        );
+
+        self.camera.zoom = vec2(self.zoom, self.zoom * screen_width() / screen_height());
+        set_camera(&self.camera);
+
+        let renderer = &mut MacroquadRenderer;
+
+        self.render_world(renderer);
+
+        set_default_camera();
+        self.profiled("render_screen_space", |game| {
+            game.render_screen_space_system(renderer)
+        });
+        #[cfg(feature = "physics")]
+        self.render_collision_group_legend(renderer);
+        self.render_hud_system(renderer);
+        #[cfg(feature = "editor")]
+        self.render_measurement_system(renderer);
+        self.render_stats_system(renderer);
+        self.toasts.render(renderer);
+        self.console.render();
+
+        #[cfg(feature = "editor")]
+        self.render_gui_system();
+
+        self.screenshot_system();
+        self.recorder_system(delta);
+    }
+
+    /// Saves the frame when asked to, so it has to run after everything else is drawn.
+    pub fn screenshot_system(&self) {
+        if !is_key_pressed(self.keys[&Actions::Screenshot]) {
+            return;
+        }
+
+        if let Err(error) = capture::save_screenshot(capture::OUTPUT_DIRECTORY) {
+            eprintln!("Failed to save screenshot: {error}");
+        }
+    }
+
+    /// Like `screenshot_system`, has to run after everything else is drawn.
+    pub fn recorder_system(&mut self, delta: f32) {
+        let toggled = is_key_pressed(self.keys[&Actions::ToggleRecording]);
+
+        if let Err(error) = self.recorder.update(toggled, delta) {
+            eprintln!("Failed to save recording: {error}");
+        }
+    }
+
+    /// Shows `text` in the corner of the screen for `duration` seconds, fading out at the end.
+    pub fn toast(&mut self, text: impl Into<String>, duration: f32) {
+        self.toasts.push(text, duration);
+    }
+
+    /// Counts the toasts down in real time, `delta` being the frame time.
+    pub fn toast_system(&mut self, delta: f32) {
+        self.toasts.update(delta);
+    }
+
+    /// Score, lives and time in the top right corner, in screen space.
+    pub fn render_hud_system(&self, renderer: &mut dyn Renderer) {
+        const FONT_SIZE: f32 = 24.0;
+
+        let seconds = self.resources.elapsed as u64;
+        let text = format!(
+            "Score {}   Lives {}   {:02}:{:02}",
+            self.resources.score,
+            self.resources.lives,
+            seconds / 60,
+            seconds % 60
+        );
+
+        let width = renderer.measure_text(&text, FONT_SIZE).x;
+        renderer.draw_text(
+            &text,
+            vec2(renderer.screen_size().x - width - 8.0, FONT_SIZE),
+            FONT_SIZE,
+            WHITE,
+        );
+    }
+
+    /// Screen space overlay with the profiler timings.
+    pub fn render_stats_system(&self, renderer: &mut dyn Renderer) {
+        if !self.profiler.enabled {
+            return;
+        }
+
+        const FONT_SIZE: f32 = 16.0;
+
+        let mut y = FONT_SIZE;
+        renderer.draw_text(
+            &format!("{} fps", get_fps()),
+            vec2(4.0, y),
+            FONT_SIZE,
+            WHITE,
+        );
+
+        #[cfg(feature = "physics")]
+        {
+            let stats = self.physics_stats();
+
+            y += FONT_SIZE;
+            let text = format!(
+                "{} active bodies, {} pairs, {} contacts, {} intersections",
+                stats.active_bodies,
+                stats.broad_phase_pairs,
+                stats.contact_pairs,
+                stats.intersection_pairs
+            );
+            renderer.draw_text(&text, vec2(4.0, y), FONT_SIZE, WHITE);
+        }
+
+        let stats = self.render_stats();
+
+        y += FONT_SIZE;
+        let text = format!(
+            "{} draw calls, {} sprites, {} culled",
+            stats.draw_calls, stats.sprites_drawn, stats.sprites_culled
+        );
+        renderer.draw_text(&text, vec2(4.0, y), FONT_SIZE, WHITE);
+
+        for timing in self.timings().iter() {
+            y += FONT_SIZE;
+
+            let text = format!(
+                "{:>8.3} ms  {}",
+                timing.average.as_secs_f64() * 1_000.0,
+                timing.name
+            );
+            renderer.draw_text(&text, vec2(4.0, y), FONT_SIZE, WHITE);
+        }
+    }
+}
+
+/// How `Application::run` drives `Game::run_logic_systems`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum UpdateMode {
+    /// Runs as many fixed steps as needed to keep up with real time. The simulation
+    /// speed is independent of the framerate, but a frame may contain zero or several
+    /// steps, which shows up as uneven motion when recording.
+    #[default]
+    FixedAccumulator,
+
+    /// Runs exactly one fixed step per rendered frame. Motion is perfectly even from
+    /// frame to frame, but the simulation runs slower or faster than real time whenever
+    /// the framerate differs from `1.0 / GOAL_DELTA_TIME`.
+    OnePerFrame,
+}
+
+/// The flow of the game as a whole, `Application::run` only simulates while `Playing`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GameState {
+    #[default]
+    Menu,
+    Playing,
+    Paused,
+    GameOver,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StateTransition {
+    Start,
+    Pause,
+    Resume,
+    Lose,
+    ToMenu,
+}
+
+impl GameState {
+    /// The state `transition` leads to from this one, or `None` when it doesn't apply.
+    pub fn transition(self, transition: StateTransition) -> Option<GameState> {
+        use GameState::*;
+        use StateTransition::*;
+
+        match (self, transition) {
+            (Menu, Start) => Some(Playing),
+            (Playing, Pause) => Some(Paused),
+            (Paused, Resume) => Some(Playing),
+            (Playing, Lose) => Some(GameOver),
+            (Paused | GameOver, ToMenu) => Some(Menu),
+
+            _ => None,
+        }
+    }
+
+    /// The title drawn over the world, nothing while playing.
+    fn title(self) -> Option<&'static str> {
+        match self {
+            GameState::Menu => Some("rust_2d_macro"),
+            GameState::Playing => None,
+            GameState::Paused => Some("Paused"),
+            GameState::GameOver => Some("Game Over"),
+        }
+    }
+}
+
+/// What the items of the state menus do when chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuAction {
+    Play,
+    Resume,
+    MainMenu,
+    Quit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FadeDir {
+    // Towards a clear screen
+    In,
+    // Towards black
+    Out,
+}
+
+/// A full screen black overlay moving its `alpha` towards the end of `direction`.
+#[derive(Debug, Clone, Copy)]
+pub struct ScreenFade {
+    pub alpha: f32,
+    pub direction: FadeDir,
+    // Change of `alpha` per second, so a whole fade takes `1.0 / speed` seconds
+    pub speed: f32,
+}
+
+impl Default for ScreenFade {
+    fn default() -> Self {
+        Self {
+            alpha: 0.0,
+            direction: FadeDir::In,
+            speed: 2.0,
+        }
+    }
+}
+
+impl ScreenFade {
+    /// Whether `alpha` has reached the end of `direction`.
+    pub fn is_done(&self) -> bool {
+        match self.direction {
+            FadeDir::In => self.alpha <= 0.0,
+            FadeDir::Out => self.alpha >= 1.0,
+        }
+    }
+
+    pub fn update(&mut self, delta: f32) {
+        let step = self.speed * delta;
+
+        self.alpha = match self.direction {
+            FadeDir::In => self.alpha - step,
+            FadeDir::Out => self.alpha + step,
+        }
+        .clamp(0.0, 1.0);
+    }
+
+    pub fn render(&self) {
+        if self.alpha > 0.0 {
+            draw_rectangle(
+                0.0,
+                0.0,
+                screen_width(),
+                screen_height(),
+                Color::new(0.0, 0.0, 0.0, self.alpha),
+            );
+        }
+    }
+}
+
+pub struct Application {
+    game: Game,
+
+    state: GameState,
+    update_mode: UpdateMode,
+
+    main_menu: Menu<MenuAction>,
+    pause_menu: Menu<MenuAction>,
+    game_over_menu: Menu<MenuAction>,
+
+    fade: ScreenFade,
+    // Applied once the screen has faded out, see `transition_with_fade`
+    pending_transition: Option<StateTransition>,
+
+    lag: f64,
+    prev_time: f64,
+
+    // The frame time rendering sees, the logic keeps stepping on the raw one
+    render_delta: Smoothed,
+}
+
+impl Default for Application {
+    fn default() -> Self {
+        Self {
+            game: Game::new(),
+
+            state: GameState::default(),
+            update_mode: UpdateMode::default(),
+
+            main_menu: Menu::new([("Play", MenuAction::Play), ("Quit", MenuAction::Quit)]),
+            pause_menu: Menu::new([
+                ("Resume", MenuAction::Resume),
+                ("Main Menu", MenuAction::MainMenu),
+                ("Quit", MenuAction::Quit),
+            ]),
+            game_over_menu: Menu::new([
+                ("Main Menu", MenuAction::MainMenu),
+                ("Quit", MenuAction::Quit),
+            ]),
+
+            fade: ScreenFade::default(),
+            pending_transition: None,
+
+            lag: 0.0,
+            // NOTE: Set when `run` starts, so loading doesn't count as the first frame
+            prev_time: 0.0,
+
+            render_delta: Smoothed::new(0.0),
+        }
+    }
+}
+
+impl Application {
+    pub fn new() -> Self {
+        let mut result = Self::default();
+
+        let bytes = include_bytes!("../assets/it.png");
+        let texture = Texture2D::from_file_with_format(bytes, Some(ImageFormat::Png));
+
+        let ecs = &mut result.game;
+
+        // ground
+
+        #[cfg(feature = "physics")]
+        {
+            let ground_entity = ecs.new_entity("Ground");
+            let collider = ColliderBuilder::cuboid(800.0, 10.0)
+                .rotation(0.0)
+                .translation(vector![500.0, 700.0])
+                .build();
+            ecs.add_fixed_collider(ground_entity, collider);
+
+            let ground_entity = ecs.new_entity("Ground");
+            let collider = ColliderBuilder::cuboid(100.0, 10.0)
+                .rotation(0.0)
+                .translation(vector![500.0, 500.0])
+                .build();
+            ecs.add_fixed_collider(ground_entity, collider);
+        }
+
+        ecs.kill_plane_y = Some(2_000.0);
+        ecs.spawn_texture = Some(texture);
+
+        // NOTE: A missing or malformed file leaves the default bindings in place
+        let _ = ecs.load_bindings(BINDINGS_PATH);
+        ecs.settings = Settings::load(SETTINGS_PATH);
+        ecs.set_render_pass_enabled(RenderPass::Contacts, ecs.settings.show_contacts);
+        ecs.set_render_pass_enabled(RenderPass::Velocities, ecs.settings.show_velocities);
+
+        // entities
+
+        #[cfg(feature = "physics")]
+        for i in 0..50 {
+            for j in 0..30 {
+                let entity = ecs.new_entity("Ball");
+
+                let (x, y) = ((i as f32 + 30.0) * 10.0, (j as f32) * 10.0);
+
+                ecs.add_texture(
+                    entity,
+                    TextureComponent {
+                        texture,
+                        size: vec2(10.0, 10.0),
+                        color: Color::from_rgba(255, 255, 255, 255),
+                        render_scale: Vec2::ONE,
+                        rotation_offset: 0.0,
+                    },
+                );
+
+                ecs.add_physics(
+                    entity,
+                    RigidBodyBuilder::dynamic()
+                        .translation(vector![x, y])
+                        .build(),
+                    ColliderBuilder::ball(5.0)
+                        .restitution(0.8)
+                        .mass(1.0)
+                        .build(),
+                );
+            }
+        }
+
+        // player
+
+        #[cfg(feature = "physics")]
+        {
+            let player_entity = ecs.new_entity("Player");
+
+            ecs.add_texture(
+                player_entity,
+                TextureComponent {
+                    texture,
+                    size: vec2(20.0, 40.0),
+                    color: Color::from_rgba(125, 72, 252, 255),
+                    render_scale: Vec2::ONE,
+                    rotation_offset: 0.0,
+                },
+            );
+
+            ecs.add_physics(
+                player_entity,
+                RigidBodyBuilder::dynamic()
+                    .translation(vector![500.0, 200.0])
+                    .linear_damping(0.99)
+                    .lock_rotations()
+                    .build(),
+                ColliderBuilder::round_cuboid(10.0, 20.0, 3.0)
+                    .restitution(1.0)
+                    .friction(0.9)
+                    .build(),
+            );
+
+            ecs.add_player_component(player_entity, PlayerComponent::default());
+            ecs.add_trail(player_entity, TrailComponent::new(30));
+        }
+
+        result
+    }
+
+    pub fn state(&self) -> GameState {
+        self.state
+    }
+
+    /// Moves to the state `transition` leads to. Returns false, changing nothing, when
+    /// it doesn't apply to the current state.
+    pub fn transition(&mut self, transition: StateTransition) -> bool {
+        let Some(state) = self.state.transition(transition) else {
+            return false;
+        };
+
+        // NOTE: Time spent outside `Playing` must not be caught up on afterwards
+        if state == GameState::Playing {
+            self.lag = 0.0;
+        }
+
+        if transition == StateTransition::Start {
+            self.game.reset_resources();
+        }
+
+        self.state = state;
+        true
+    }
+
+    pub fn start_fade(&mut self, direction: FadeDir) {
+        self.fade.direction = direction;
+    }
+
+    /// Fades out, applies `transition` and fades back in. Returns false, changing
+    /// nothing, when it doesn't apply or another one is already underway.
+    pub fn transition_with_fade(&mut self, transition: StateTransition) -> bool {
+        if self.pending_transition.is_some() || self.state.transition(transition).is_none() {
+            return false;
+        }
+
+        self.pending_transition = Some(transition);
+        self.start_fade(FadeDir::Out);
+        true
+    }
+
+    /// Runs on real time, so the fade is unaffected by `time_scale` and pausing.
+    fn fade_system(&mut self, delta: f32) {
+        self.fade.update(delta);
+
+        if self.fade.is_done() {
+            if let Some(transition) = self.pending_transition.take() {
+                self.transition(transition);
+                self.start_fade(FadeDir::In);
+            }
+        }
+    }
+
+    /// The menu shown in the current state, if any.
+    fn menu(&self) -> Option<&Menu<MenuAction>> {
+        match self.state {
+            GameState::Menu => Some(&self.main_menu),
+            GameState::Playing => None,
+            GameState::Paused => Some(&self.pause_menu),
+            GameState::GameOver => Some(&self.game_over_menu),
+        }
+    }
+
+    fn menu_mut(&mut self) -> Option<&mut Menu<MenuAction>> {
+        match self.state {
+            GameState::Menu => Some(&mut self.main_menu),
+            GameState::Playing => None,
+            GameState::Paused => Some(&mut self.pause_menu),
+            GameState::GameOver => Some(&mut self.game_over_menu),
+        }
+    }
+
+    fn menu_action(&mut self, action: MenuAction) {
+        match action {
+            MenuAction::Play => self.transition_with_fade(StateTransition::Start),
+            MenuAction::Resume => self.transition(StateTransition::Resume),
+            MenuAction::MainMenu => self.transition_with_fade(StateTransition::ToMenu),
+            MenuAction::Quit => std::process::exit(0),
+        };
+    }
+
+    /// Maps this frame's input onto transitions, except while the console takes the keyboard.
+    fn state_input_system(&mut self) {
+        let keys = &self.game.keys;
+
+        // NOTE: Escape clears a measurement first, see `Game::editor_input_system`
+        #[cfg(feature = "editor")]
+        let escape_taken =
+            self.game.is_measuring() && keys[&Actions::QuitImmediately] == KeyCode::Escape;
+        #[cfg(not(feature = "editor"))]
+        let escape_taken = false;
+
+        if !escape_taken && is_key_pressed(keys[&Actions::QuitImmediately]) {
+            std::process::exit(0);
+        }
+
+        // NOTE: The state is about to change, so its input no longer applies
+        if self.game.console.open || self.pending_transition.is_some() {
+            return;
+        }
+
+        let pause_pressed = is_key_pressed(keys[&Actions::Pause]);
+        let input = MenuInput {
+            up: is_key_pressed(keys[&Actions::MoveUp]) || is_key_pressed(KeyCode::Up),
+            down: is_key_pressed(keys[&Actions::MoveDown]) || is_key_pressed(KeyCode::Down),
+            confirm: is_key_pressed(keys[&Actions::Confirm]),
+        };
+
+        match self.state {
+            GameState::Playing if pause_pressed => {
+                self.transition(StateTransition::Pause);
+                return;
+            }
+
+            GameState::Paused if pause_pressed => {
+                self.transition(StateTransition::Resume);
+                return;
+            }
+
+            _ => {}
+        }
+
+        let Some(action) = self.menu_mut().and_then(|menu| menu.navigate(input)) else {
+            return;
+        };
+
+        self.menu_action(action);
+    }
+
+    /// Dims the screen and draws the title and menu of non-playing states, in screen space.
+    fn render_state_overlay(&self) {
+        let Some(title) = self.state.title() else {
+            return;
+        };
+
+        const TITLE_SIZE: f32 = 48.0;
+
+        let (width, height) = (screen_width(), screen_height());
+        draw_rectangle(0.0, 0.0, width, height, Color::new(0.0, 0.0, 0.0, 0.6));
+
+        let dimensions = measure_text(title, None, TITLE_SIZE as u16, 1.0);
+        draw_text(
+            title,
+            (width - dimensions.width) * 0.5,
+            height * 0.35,
+            TITLE_SIZE,
+            WHITE,
+        );
+
+        if let Some(menu) = self.menu() {
+            menu.render(height * 0.35 + TITLE_SIZE);
+        }
+    }
+
+    /// How much of the previous frame time the camera and animations keep each frame,
+    /// in `0.0..1.0`. 0 shows every hitch as is, higher values soften them.
+    pub fn set_delta_smoothing(&mut self, smoothing: f32) {
+        self.render_delta.smoothing = smoothing.clamp(0.0, 0.99);
+    }
+
+    pub fn set_update_mode(&mut self, update_mode: UpdateMode) {
+        self.update_mode = update_mode;
+        self.lag = 0.0;
+    }
+
+    /// Runs the logic systems for a frame which took `delta` seconds and
+    /// returns the number of fixed steps taken.
+    pub fn update(&mut self, delta: f64) -> usize {
+        match self.update_mode {
+            UpdateMode::FixedAccumulator => {
+                /*
+                    NOTE(Erik): Ensure that logic systems run on a fixed delta while
+                                still calling the rendering functions as quickly as possible.
+                */
+                let mut steps = 0;
+
+                self.lag += delta;
+                while self.lag >= GOAL_DELTA_TIME {
+                    self.game.run_logic_systems(GOAL_DELTA_TIME as f32);
+                    self.lag -= GOAL_DELTA_TIME;
+                    steps += 1;
+                }
+
+                steps
+            }
+
+            UpdateMode::OnePerFrame => {
+                self.game.run_logic_systems(GOAL_DELTA_TIME as f32);
+                1
+            }
+        }
+    }
+
+    /// How far real time has moved past the last fixed step, as a fraction of a step.
+    pub fn interpolation_alpha(&self) -> f32 {
+        match self.update_mode {
+            UpdateMode::FixedAccumulator => (self.lag / GOAL_DELTA_TIME).clamp(0.0, 1.0) as f32,
+
+            // NOTE: Every frame renders exactly the latest step
+            UpdateMode::OnePerFrame => 1.0,
+        }
+    }
+
+    pub async fn run(&mut self) {
+        self.prev_time = get_time();
+
+        loop {
+            let time = get_time();
+            let delta = time - self.prev_time;
+            let render_delta = self.render_delta.update(delta as f32);
+
+            self.state_input_system();
+            self.fade_system(render_delta);
+
+            if self.state == GameState::Playing {
+                #[cfg(feature = "physics")]
+                self.game.player_input_system();
+                self.update(delta);
+
+                if self.game.is_out_of_lives() {
+                    self.transition_with_fade(StateTransition::Lose);
+                }
+            }
+
+            let alpha = self.interpolation_alpha();
+            self.game.run_rendering_systems(render_delta, alpha);
+            self.render_state_overlay();
+            self.fade.render();
+            self.prev_time = time;
+
+            next_frame().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use renderer::{DrawCall, RecordingRenderer};
+
+    /// A file of its own in the temp directory, so tests running in parallel don't collide.
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("rust_2d_macro_{}_{name}", std::process::id()))
+    }
+
+    #[test]
+    fn one_per_frame_steps_once_whatever_the_frame_time() {
+        let mut app = Application::default();
+        app.set_update_mode(UpdateMode::OnePerFrame);
+
+        for delta in [0.0, GOAL_DELTA_TIME / 3.0, GOAL_DELTA_TIME, 1.0] {
+            assert_eq!(app.update(delta), 1);
+        }
+        assert_eq!(
+            app.game.resources.elapsed,
+            4.0 * GOAL_DELTA_TIME as f32 as f64
+        );
+
+        app.set_update_mode(UpdateMode::FixedAccumulator);
+        assert_eq!(app.update(0.0), 0);
+        assert_eq!(app.update(GOAL_DELTA_TIME * 3.5), 3);
+    }
+
+    #[test]
+    fn interpolation_alpha_is_the_leftover_lag_in_steps() {
+        let mut app = Application::default();
+        assert_eq!(app.interpolation_alpha(), 0.0);
+
+        assert_eq!(app.update(GOAL_DELTA_TIME * 2.25), 2);
+        assert!((app.interpolation_alpha() - 0.25).abs() < 1e-4);
+
+        assert_eq!(app.update(GOAL_DELTA_TIME * 0.5), 0);
+        assert!((app.interpolation_alpha() - 0.75).abs() < 1e-4);
+
+        app.set_update_mode(UpdateMode::OnePerFrame);
+        app.update(GOAL_DELTA_TIME * 0.5);
+        assert_eq!(app.interpolation_alpha(), 1.0);
+    }
+
+    #[test]
+    fn validate_reports_flags_and_rows_out_of_sync() {
+        let mut game = Game::default();
+        let flagged = game.new_entity("Flagged");
+        let stored = game.new_entity("Stored");
+        game.add_trail(stored, TrailComponent::new(4));
+        assert_eq!(game.validate(), Ok(()));
+
+        game.add_flag(flagged, components::TRAIL);
+        game.remove_flag(stored, components::TRAIL);
+
+        assert_eq!(
+            game.validate(),
+            Err(vec![
+                format!("{flagged:?} (Flagged) has components::TRAIL but no row in self.trail_container"),
+                format!("{stored:?} (Stored) has a row in self.trail_container but not components::TRAIL"),
+            ])
+        );
+    }
+
+    #[test]
+    fn bindings_survive_a_save_and_load() {
+        let path = temp_path("bindings.cfg");
+
+        let mut saved = Game::new();
+        saved.keys.insert(Actions::MoveUp, KeyCode::Space);
+        saved.keys.insert(Actions::Dash, KeyCode::Kp5);
+        saved.save_bindings(&path).unwrap();
+
+        let mut loaded = Game::new();
+        loaded.load_bindings(&path).unwrap();
+        assert!(loaded.keys == saved.keys);
+
+        fs::write(&path, "MoveUp=Enter\nDash=NoSuchKey\n").unwrap();
+        assert!(loaded.load_bindings(&path).is_err());
+        assert!(loaded.keys == saved.keys, "a bad file changes nothing");
+
+        fs::remove_file(&path).unwrap();
+        assert!(loaded.load_bindings(&path).is_err());
+        assert!(loaded.keys == saved.keys);
+    }
+
+    #[test]
+    fn visible_tiles_are_the_ones_overlapping_the_view() {
+        let mut tilemap = TilemapComponent::new(20, 10, 16.0, Texture2D::empty());
+        tilemap.origin = vec2(-32.0, 0.0);
+
+        assert_eq!(
+            tilemap.visible_range(Rect::new(0.0, 10.0, 40.0, 20.0)),
+            (2..5, 0..2)
+        );
+        assert_eq!(
+            tilemap.visible_range(Rect::new(-1_000.0, -1_000.0, 10.0, 10.0)),
+            (0..0, 0..0)
+        );
+        assert_eq!(
+            tilemap.visible_range(Rect::new(-1_000.0, -1_000.0, 5_000.0, 5_000.0)),
+            (0..20, 0..10)
+        );
+    }
+
+    /// A blank `size` sprite.
+    pub(super) fn texture(size: Vec2) -> TextureComponent {
+        TextureComponent {
+            texture: Texture2D::empty(),
+            size,
+            color: WHITE,
+            render_scale: Vec2::ONE,
+            rotation_offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn query2_yields_the_enabled_entities_with_both_components() {
+        let mut game = Game::default();
+        let entities = [Some(1), None, Some(3), Some(4)].map(|trail| {
+            let entity = game.new_entity("Sprite");
+            game.add_texture(entity, texture(vec2(trail.unwrap_or(0) as f32, 1.0)));
+            if let Some(max) = trail {
+                game.add_trail(entity, TrailComponent::new(max));
+            }
+            entity
+        });
+        game.set_enabled(entities[2], false);
+
+        let found = game
+            .query2::<TrailComponent, TextureComponent>()
+            .map(|(entity, trail, texture)| (entity, trail.max, texture.size.x))
+            .collect::<Vec<_>>();
+        assert_eq!(found, vec![(entities[0], 1, 1.0), (entities[3], 4, 4.0)]);
+
+        // NOTE: Asking for `DisabledComponent` opts into the disabled entities
+        let disabled = game
+            .query2::<DisabledComponent, TrailComponent>()
+            .map(|(entity, _disabled, _trail)| entity)
+            .collect::<Vec<_>>();
+        assert_eq!(disabled, vec![entities[2]]);
+    }
+
+    #[test]
+    fn flags_on_removed_entities_fail_without_panicking() {
+        let mut game = Game::default();
+        let entity = game.new_entity("Gone");
+        game.remove_entity(entity);
+
+        assert!(!game.try_add_flag(entity, components::TRAIL));
+        assert!(!game.try_remove_flag(entity, components::TRAIL));
+        assert!(!game.has_flag(entity, components::TRAIL));
+
+        let alive = game.new_entity("Alive");
+        assert!(game.try_add_flag(alive, components::TRAIL));
+        assert!(game.has_flag(alive, components::TRAIL));
+    }
+
+    #[test]
+    #[should_panic(expected = "add_flag on the removed entity")]
+    fn add_flag_on_a_removed_entity_panics() {
+        let mut game = Game::default();
+        let entity = game.new_entity("Gone");
+        game.remove_entity(entity);
+
+        game.add_flag(entity, components::TRAIL);
+    }
+
+    #[test]
+    fn despawned_entities_stay_dead_when_their_slot_is_reused() {
+        let mut game = Game::default();
+        let old = game.new_entity("Old");
+        game.remove_entity(old);
+        let new = game.new_entity("New");
+
+        assert_ne!(old, new);
+        assert!(!game.is_alive(old));
+        assert!(game.is_alive(new));
+    }
+
+    #[test]
+    fn spawns_queued_by_a_system_exist_after_its_flush() {
+        let mut game = Game::default();
+
+        game.run_system("spawner", |game| {
+            game.commands.spawn("Spawned", |game, entity| {
+                game.add_trail(entity, TrailComponent::new(3));
+            });
+            assert!(game.entities.is_empty(), "spawns wait for the flush");
+        });
+
+        let spawned = game.dump();
+        assert_eq!(spawned.len(), 1);
+        let (entity, label, bitset, _position) = spawned[0];
+        assert_eq!(label, "Spawned");
+        assert!(bitset.contains(components::TRAIL));
+        assert_eq!(game.trail_container[entity].max, 3);
+    }
+
+    #[test]
+    fn interpolating_halfway_averages_the_two_snapshots() {
+        let mut interp = NetInterpComponent::new(vec2(0.0, 0.0), 0.5, 0.0);
+        interp.push(vec2(10.0, 20.0), 1.5, 1.0);
+
+        let (position, rotation) = interp.sample(1.0 + NET_SNAPSHOT_INTERVAL / 2.0);
+        assert!(position.abs_diff_eq(vec2(5.0, 10.0), 1e-4), "{position}");
+        assert!((rotation - 1.0).abs() < 1e-4);
+
+        assert_eq!(interp.sample(1.0), (vec2(0.0, 0.0), 0.5));
+        assert_eq!(interp.sample(0.0), (vec2(0.0, 0.0), 0.5));
+    }
+
+    #[test]
+    fn interpolated_rotations_turn_the_short_way() {
+        let mut interp = NetInterpComponent::new(Vec2::ZERO, PI - 0.1, 0.0);
+        interp.push(Vec2::ZERO, -PI + 0.1, 0.0);
+
+        let (_position, rotation) = interp.sample(NET_SNAPSHOT_INTERVAL / 2.0);
+        assert!((rotation - PI).abs() < 1e-4, "{rotation}");
+    }
+
+    #[test]
+    fn late_snapshots_extrapolate_a_bounded_distance() {
+        let mut interp = NetInterpComponent::new(vec2(0.0, 0.0), 0.0, 0.0);
+        interp.push(vec2(10.0, 0.0), 0.0, 0.0);
+
+        let furthest = 10.0 * (1.0 + NET_MAX_EXTRAPOLATION);
+        let (position, _rotation) = interp.sample(100.0);
+        assert!((position.x - furthest).abs() < 1e-4);
+    }
+
+    #[test]
+    fn screen_space_sprites_are_centered_on_their_anchor_wherever_the_camera_is() {
+        let mut game = Game::default();
+        let hud = game.new_entity("Hud");
+        game.add_texture(hud, texture(vec2(40.0, 20.0)));
+        game.add_screen_space(
+            hud,
+            ScreenSpaceComponent {
+                anchor: vec2(100.0, 50.0),
+            },
+        );
+
+        for target in [vec2(0.0, 0.0), vec2(-3_000.0, 700.0)] {
+            game.camera.target = target;
+
+            let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+            game.render_screen_space_system(&mut renderer);
+
+            let [DrawCall::Texture {
+                position,
+                dest_size,
+                ..
+            }] = renderer.calls[..]
+            else {
+                panic!("expected one sprite, found {:?}", renderer.calls);
+            };
+            assert_eq!(position, vec2(80.0, 40.0));
+            assert_eq!(dest_size, Some(vec2(40.0, 20.0)));
+        }
+    }
+
+    #[test]
+    fn disabled_render_passes_are_skipped() {
+        // The draw calls and timed passes of a frame with a trail
+        let render = |enabled: bool| {
+            let mut game = Game::default();
+            game.profiler.enabled = true;
+            game.set_render_pass_enabled(RenderPass::Trails, enabled);
+
+            let entity = game.new_entity("Trail");
+            let mut trail = TrailComponent::new(3);
+            trail
+                .positions
+                .extend([vec2(500.0, 500.0), vec2(510.0, 500.0), vec2(520.0, 500.0)]);
+            game.add_trail(entity, trail);
+
+            let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+            game.render_world(&mut renderer);
+
+            let lines = renderer
+                .calls
+                .iter()
+                .filter(|call| matches!(call, DrawCall::Line { .. }))
+                .count();
+            let timed = game.timings().get(RenderPass::Trails.name()).is_some();
+            (lines, timed)
+        };
+
+        assert_eq!(render(true), (2, true));
+        assert_eq!(render(false), (0, false));
+    }
+
+    fn parallax_layer(size: Vec2, factor: f32) -> ParallaxLayer {
+        let mut texture = miniquad::Texture::empty();
+        (texture.width, texture.height) = (size.x as u32, size.y as u32);
+
+        ParallaxLayer {
+            texture: Texture2D::from_miniquad_texture(texture),
+            factor,
+        }
+    }
+
+    #[test]
+    fn parallax_layers_follow_the_camera_by_their_factor() {
+        let layer = parallax_layer(vec2(100.0, 50.0), 0.5);
+        assert_eq!(layer.offset(vec2(200.0, -40.0)), vec2(100.0, -20.0));
+        assert_eq!(
+            parallax_layer(vec2(100.0, 50.0), 0.0).offset(vec2(200.0, -40.0)),
+            Vec2::ZERO
+        );
+
+        let tiles = layer
+            .tiles(Rect::new(90.0, -40.0, 150.0, 60.0), vec2(200.0, -40.0))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            tiles,
+            vec![
+                vec2(0.0, -70.0),
+                vec2(100.0, -70.0),
+                vec2(200.0, -70.0),
+                vec2(0.0, -20.0),
+                vec2(100.0, -20.0),
+                vec2(200.0, -20.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn spawns_over_the_budget_wait_for_later_frames() {
+        let mut game = Game::default();
+        game.set_max_spawns_per_frame(Some(10));
+
+        for _ in 0..100 {
+            game.commands.spawn("Particle", |_game, _entity| {});
+        }
+        game.flush_commands();
+        assert_eq!((game.entities.len(), game.deferred_spawns()), (10, 90));
+
+        for frame in 2..=10 {
+            game.begin_spawn_frame();
+            assert_eq!(game.entities.len(), frame * 10);
+        }
+        assert_eq!(game.deferred_spawns(), 0);
+
+        game.begin_spawn_frame();
+        assert_eq!(game.entities.len(), 100);
+    }
+
+    #[test]
+    fn all_actions_lists_every_variant_once() {
+        // NOTE: Exhaustive, so a new action doesn't compile until it is added here too
+        let expected = |action: Actions| match action {
+            Actions::QuitImmediately => 0,
+            Actions::MoveRight => 1,
+            Actions::MoveLeft => 2,
+            Actions::MoveUp => 3,
+            Actions::MoveDown => 4,
+            Actions::SelectNext => 5,
+            Actions::FrameSelected => 6,
+            Actions::DumpWorld => 7,
+            Actions::ToggleProfiler => 8,
+            Actions::ToggleConsole => 9,
+            Actions::Screenshot => 10,
+            Actions::ToggleRecording => 11,
+            Actions::Dash => 12,
+            Actions::Pause => 13,
+            Actions::Confirm => 14,
+        };
+
+        let all = Actions::all();
+        assert_eq!(all.len(), 15);
+        for (i, action) in all.iter().enumerate() {
+            assert_eq!(expected(*action), i);
+            assert_eq!(Actions::from_name(&action.name()), Some(*action));
+        }
+
+        assert_eq!(Actions::MoveRight.label(), "Move Right");
+        assert_eq!(Actions::Dash.label(), "Dash");
+    }
+
+    #[test]
+    fn game_flow_goes_from_the_menu_to_playing_and_pausing() {
+        let mut app = Application::default();
+        assert_eq!(app.state(), GameState::Menu);
+        assert!(!app.transition(StateTransition::Pause));
+
+        app.game.add_score(10);
+        assert!(app.transition(StateTransition::Start));
+        assert_eq!(app.state(), GameState::Playing);
+        assert_eq!(app.game.score(), 0, "starting resets the resources");
+
+        assert!(app.transition(StateTransition::Pause));
+        assert_eq!(app.state(), GameState::Paused);
+        assert!(!app.transition(StateTransition::Start));
+        assert_eq!(app.state(), GameState::Paused);
+
+        app.lag = GOAL_DELTA_TIME * 0.5;
+        assert!(app.transition(StateTransition::Resume));
+        assert_eq!(app.state(), GameState::Playing);
+        assert_eq!(app.lag, 0.0, "paused time isn't caught up on");
+
+        assert!(app.transition(StateTransition::Lose));
+        assert!(app.transition(StateTransition::ToMenu));
+        assert_eq!(app.state(), GameState::Menu);
+    }
+
+    #[test]
+    fn fading_out_reaches_black_over_its_duration_then_fades_in() {
+        let mut app = Application::default();
+        app.game.set_time_scale(0.1);
+        let duration = 1.0 / app.fade.speed;
+
+        assert!(app.transition_with_fade(StateTransition::Start));
+        assert_eq!(app.fade.alpha, 0.0);
+
+        app.fade_system(duration * 0.5);
+        assert!(
+            (app.fade.alpha - 0.5).abs() < 1e-6,
+            "the time scale is ignored"
+        );
+        assert_eq!(app.state(), GameState::Menu);
+
+        app.fade_system(duration * 0.5);
+        assert_eq!(app.fade.alpha, 1.0);
+        assert_eq!(app.state(), GameState::Playing);
+        assert_eq!(app.fade.direction, FadeDir::In);
+
+        app.fade_system(duration);
+        assert_eq!(app.fade.alpha, 0.0);
+    }
+
+    #[test]
+    fn score_and_lives_go_through_the_resources() {
+        let mut game = Game::default();
+
+        game.add_score(5);
+        game.add_score(u64::MAX);
+        assert_eq!(game.resources().score, u64::MAX, "the score saturates");
+
+        game.set_lives(0);
+        assert!(game.is_out_of_lives());
+
+        game.reset_resources();
+        assert_eq!(*game.resources(), Resources::default());
+    }
+
+    #[test]
+    fn games_with_the_same_seed_draw_the_same_numbers() {
+        fn draws(game: &mut Game) -> Vec<f32> {
+            let rect = Rect::new(-10.0, 20.0, 30.0, 40.0);
+
+            (0..32)
+                .flat_map(|_| {
+                    let point = game.random_vec2_in(rect);
+                    let direction = game.random_unit_vec2();
+                    [game.random_range(-1.0, 1.0), point.x, point.y, direction.x]
+                })
+                .collect()
+        }
+
+        let (mut a, mut b, mut c) = (Game::default(), Game::default(), Game::default());
+        a.seed_rng(7);
+        b.seed_rng(7);
+        c.seed_rng(8);
+
+        let draws_a = draws(&mut a);
+        assert_eq!(draws_a, draws(&mut b));
+        assert_ne!(draws_a, draws(&mut c));
+
+        // NOTE: Restoring the state replays the draws made after it
+        let state = a.rng_state();
+        let next = draws(&mut a);
+        a.set_rng_state(state);
+        assert_eq!(next, draws(&mut a));
+    }
+
+    #[test]
+    fn each_collision_group_keeps_its_own_color() {
+        assert_eq!(group_color(0), GRAY);
+        assert_eq!(group_color(u32::MAX), WHITE);
+
+        let colors = (0..32).map(|bit| group_color(1 << bit)).collect::<Vec<_>>();
+        // NOTE: Pinned, so that a layer keeps its color across versions as well
+        for (memberships, expected) in [
+            (1 << 0, Color::new(0.92, 0.28, 0.28, 1.0)),
+            (1 << 1, Color::new(0.28, 0.46675, 0.92, 1.0)),
+            (1 << 31, Color::new(0.92, 0.89077, 0.28, 1.0)),
+            (0b11, Color::new(0.51467, 0.28, 0.92, 1.0)),
+        ] {
+            let color = group_color(memberships);
+            assert!(
+                color.to_vec().abs_diff_eq(expected.to_vec(), 1e-4),
+                "{memberships:#b}: {color:?}"
+            );
+        }
+
+        for (i, a) in colors.iter().enumerate() {
+            for b in &colors[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn queued_despawns_stay_valid_until_the_end_of_the_tick() {
+        let mut game = Game::default();
+        let entity = game.new_entity("Sprite");
+        game.add_texture(entity, texture(Vec2::ONE));
+
+        game.run_system("test", |game| game.commands.despawn(entity));
+        assert!(game.is_alive(entity));
+        assert!(game.is_despawning(entity));
+        assert!(game.texture_container.contains_key(entity));
+
+        game.flush_despawns();
+        assert!(!game.is_alive(entity));
+        assert!(!game.is_despawning(entity));
+        assert!(!game.texture_container.contains_key(entity));
+    }
+
+    // NOTE: Also run with `--no-default-features`, which leaves only the ECS
+    #[test]
+    fn the_ecs_spawns_queries_and_ticks_on_its_own() {
+        let mut game = Game::default();
+        let sprite = game.new_entity("Sprite");
+        game.add_texture(sprite, texture(Vec2::ONE));
+
+        game.commands.spawn("Trail", |game, entity| {
+            game.add_texture(entity, texture(Vec2::ONE));
+            game.add_trail(entity, TrailComponent::new(4));
+        });
+        game.run_logic_systems(GOAL_DELTA_TIME as f32);
+
+        let trails = game
+            .query2::<TextureComponent, TrailComponent>()
+            .map(|(entity, _texture, trail)| (game.has_flag(entity, components::TRAIL), trail.max))
+            .collect::<Vec<_>>();
+        assert_eq!(trails, vec![(true, 4)]);
+        assert_eq!(game.entities.len(), 2);
+
+        game.commands.despawn(sprite);
+        game.run_logic_systems(GOAL_DELTA_TIME as f32);
+        assert!(!game.is_alive(sprite));
+        assert_eq!(game.entities.len(), 1);
+    }
+
+    #[test]
+    fn flag_changes_are_reported_with_the_old_and_new_flags() {
+        let mut game = Game::default();
+        let entity = game.new_entity("Sprite");
+
+        game.add_flag(entity, components::CHECKPOINT);
+        assert!(game.events().is_empty(), "off unless asked for");
+
+        game.set_flag_events(true);
+        game.add_flag(entity, components::SCREEN_SPACE);
+        game.add_flag(entity, components::SCREEN_SPACE);
+        game.remove_flag(entity, components::CHECKPOINT);
+
+        let checkpoint = BitSet::empty().with(components::CHECKPOINT);
+        let both = checkpoint.with(components::SCREEN_SPACE);
+        assert_eq!(
+            game.events(),
+            [
+                GameEvent::FlagsChanged {
+                    entity,
+                    old: checkpoint,
+                    new: both,
+                },
+                GameEvent::FlagsChanged {
+                    entity,
+                    old: both,
+                    new: BitSet::empty().with(components::SCREEN_SPACE),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn flags_round_trip_through_their_names() {
+        use components::{flags_to_names, from_name, names_to_flags};
+
+        let combinations = [
+            BitSet::empty(),
+            BitSet::empty().with(components::TEXTURE),
+            BitSet::empty()
+                .with(components::RIGIDBODY)
+                .with(components::COLLIDER)
+                .with(components::PLAYER),
+            components::every_component().fold(BitSet::empty(), |bitset, flag| bitset.with(flag)),
+        ];
+
+        for bitset in combinations {
+            let names = flags_to_names(bitset);
+            assert_eq!(names_to_flags(&names), Ok(bitset), "{names:?}");
+        }
+
+        assert_eq!(
+            flags_to_names(
+                BitSet::empty()
+                    .with(components::TIMER)
+                    .with(components::TEXTURE)
+            ),
+            ["TEXTURE", "TIMER"]
+        );
+        assert_eq!(
+            names_to_flags(&" TRAIL|HOMING".split('|').collect::<Vec<_>>()),
+            Ok(BitSet::empty()
+                .with(components::TRAIL)
+                .with(components::HOMING))
+        );
+
+        assert_eq!(from_name("texture"), None);
+        assert_eq!(names_to_flags(&["TEXTURE", "WINGS", "NOPE"]), Err("WINGS"));
+    }
+
+    #[test]
+    fn snapped_points_land_on_whole_screen_pixels() {
+        let screen_size = vec2(800.0, 600.0);
+        // NOTE: Three screen pixels per world unit
+        let camera = Camera2D {
+            target: vec2(10.3, -4.7),
+            zoom: vec2(3.0 * 2.0 / 800.0, 3.0 * 2.0 / 600.0),
+            ..Default::default()
+        };
+
+        for point in [
+            vec2(0.0, 0.0),
+            vec2(10.1, -4.9),
+            vec2(12.345, 6.789),
+            vec2(-0.17, 0.5),
+        ] {
+            let snapped = snap_to_pixel(point, &camera, screen_size);
+            let pixel = world_to_screen(&camera, snapped, screen_size);
+
+            assert!(pixel.abs_diff_eq(pixel.round(), 1e-2), "{point} -> {pixel}");
+            assert!(
+                (snapped - point).abs().max_element() <= 0.5 / 3.0 + 1e-4,
+                "{point} -> {snapped}"
+            );
+        }
+    }
+
+    #[test]
+    fn external_ids_stay_put_when_other_entities_are_removed() {
+        let mut game = Game::default();
+        let entities = ["A", "B", "C", "D"].map(|label| game.new_entity(label));
+        let ids = entities.map(|entity| game.external_id_of(entity).unwrap());
+
+        game.remove_entity(entities[1]);
+        game.remove_entity(entities[2]);
+
+        // NOTE: Reuses the slot of a removed entity, but never its external id
+        let reused = game.new_entity("E");
+        let reused_id = game.external_id_of(reused).unwrap();
+        assert!(!ids.contains(&reused_id));
+
+        for i in [0, 3] {
+            assert_eq!(game.external_id_of(entities[i]), Some(ids[i]));
+            assert_eq!(game.entity_by_external_id(ids[i]), Some(entities[i]));
+        }
+        for i in [1, 2] {
+            assert_eq!(game.external_id_of(entities[i]), None);
+            assert_eq!(game.entity_by_external_id(ids[i]), None);
+        }
+        assert_eq!(game.entity_by_external_id(reused_id), Some(reused));
+    }
+
+    #[test]
+    fn timers_expire_after_their_duration_and_can_be_restarted() {
+        let mut game = Game::default();
+        let entity = game.new_entity("Dasher");
+        assert!(!game.timer_running(entity, "dash") && !game.timer_expired(entity, "dash"));
+
+        game.set_timer(entity, "dash", 0.5);
+        game.timer_system(0.25);
+        assert!(game.timer_running(entity, "dash"));
+        assert!(!game.timer_expired(entity, "dash"));
+
+        game.timer_system(0.25);
+        assert!(!game.timer_running(entity, "dash"));
+        assert!(game.timer_expired(entity, "dash"));
+
+        game.set_timer(entity, "dash", 0.5);
+        assert!(game.timer_running(entity, "dash"));
+        game.timer_system(1.0);
+        assert!(game.timer_expired(entity, "dash"));
+        assert_eq!(game.timer_container[entity].remaining("dash"), Some(0.0));
+
+        game.reset_timer(entity, "dash");
+        assert!(!game.timer_running(entity, "dash") && !game.timer_expired(entity, "dash"));
+    }
+}
+
+#[cfg(all(test, feature = "physics"))]
+mod physics_tests {
+    use super::*;
+    use renderer::{DrawCall, RecordingRenderer};
+
+    const DELTA: f32 = GOAL_DELTA_TIME as f32;
+
+    /// Runs `ticks` logic ticks and returns every event they emitted.
+    fn step(game: &mut Game, ticks: usize) -> Vec<GameEvent> {
+        let mut events = vec![];
+
+        for _ in 0..ticks {
+            game.run_logic_systems(DELTA);
+            events.extend_from_slice(game.events());
+        }
+
+        events
+    }
+
+    /// A game without gravity, so bodies stay where they are put.
+    fn weightless() -> Game {
+        Game {
+            gravity: vector![0.0, 0.0],
+            ..Default::default()
+        }
+    }
+
+    fn add_ball(game: &mut Game, position: Vec2) -> Entity {
+        let entity = game.new_entity("Ball");
+        game.add_physics(
+            entity,
+            RigidBodyBuilder::dynamic()
+                .translation(vector![position.x, position.y])
+                .build(),
+            ColliderBuilder::ball(5.0).build(),
+        );
+
+        entity
+    }
+
+    /// A ball drawn as a `size` sprite.
+    fn add_sprite(game: &mut Game, position: Vec2, size: Vec2) -> Entity {
+        let entity = add_ball(game, position);
+        game.add_texture(entity, super::tests::texture(size));
+
+        entity
+    }
+
+    /// Every sprite `render_sprites_system` draws, as `(corner, size)`.
+    fn drawn_sprites(game: &Game) -> Vec<(Vec2, Vec2)> {
+        let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+        game.render_sprites_system(&mut renderer);
+
+        renderer
+            .calls
+            .into_iter()
+            .map(|call| match call {
+                DrawCall::Texture {
+                    position,
+                    dest_size,
+                    ..
+                } => (position, dest_size.unwrap()),
+                call => panic!("sprites are textures, found {call:?}"),
+            })
+            .collect()
+    }
+
+    fn add_ground(game: &mut Game, y: f32) -> Entity {
+        let entity = game.new_entity("Ground");
+        game.add_physics(
+            entity,
+            RigidBodyBuilder::fixed()
+                .translation(vector![0.0, y])
+                .build(),
+            ColliderBuilder::cuboid(500.0, 10.0).build(),
+        );
+
+        entity
+    }
+
+    fn add_player(game: &mut Game, position: Vec2) -> Entity {
+        let entity = game.new_entity("Player");
+        game.add_physics(
+            entity,
+            RigidBodyBuilder::dynamic()
+                .translation(vector![position.x, position.y])
+                .lock_rotations()
+                .build(),
+            ColliderBuilder::cuboid(10.0, 20.0).friction(0.0).build(),
+        );
+        game.add_player_component(entity, PlayerComponent::default());
+
+        entity
+    }
+
+    /// Holds `movement` for `ticks` ticks, like `player_input_system` would.
+    fn hold(game: &mut Game, movement: Vec2, ticks: usize) {
+        for _ in 0..ticks {
+            game.player_input.movement = movement;
+            step(game, 1);
+        }
+    }
+
+    #[test]
+    fn trigger_reports_entering_and_leaving() {
+        let mut game = weightless();
+
+        let trigger = game.new_entity("Trigger");
+        game.add_trigger(
+            trigger,
+            TriggerComponent { id: 1 },
+            ColliderBuilder::cuboid(20.0, 20.0).build(),
+        );
+        let ball = add_ball(&mut game, vec2(200.0, 0.0));
+
+        assert_eq!(step(&mut game, 3), vec![]);
+
+        game.set_position(ball, vec2(0.0, 0.0));
+        assert_eq!(
+            step(&mut game, 3),
+            vec![GameEvent::TriggerEnter {
+                trigger,
+                entity: ball
+            }]
+        );
+
+        game.set_position(ball, vec2(200.0, 0.0));
+        assert_eq!(
+            step(&mut game, 3),
+            vec![GameEvent::TriggerExit {
+                trigger,
+                entity: ball
+            }]
+        );
+    }
+
+    #[test]
+    fn killed_player_respawns_at_the_last_checkpoint() {
+        let mut game = weightless();
+
+        let checkpoint = game.new_entity("Checkpoint");
+        game.add_checkpoint(
+            checkpoint,
+            TriggerComponent { id: 1 },
+            ColliderBuilder::cuboid(20.0, 20.0)
+                .translation(vector![100.0, 0.0])
+                .build(),
+        );
+        let player = add_ball(&mut game, vec2(0.0, 0.0));
+        game.add_player_component(player, PlayerComponent::default());
+        assert_eq!(game.player_container[player].respawn_point, vec2(0.0, 0.0));
+
+        game.set_position(player, vec2(100.0, 0.0));
+        step(&mut game, 3);
+        assert_eq!(
+            game.player_container[player].respawn_point,
+            vec2(100.0, 0.0)
+        );
+
+        game.set_position(player, vec2(300.0, 0.0));
+        step(&mut game, 1);
+        game.kill(player);
+        step(&mut game, 1);
+
+        assert_eq!(game.position(player), Some(vec2(100.0, 0.0)));
+    }
+
+    #[test]
+    fn trail_never_holds_more_than_max_positions() {
+        let mut game = Game::default();
+
+        let ball = add_ball(&mut game, vec2(0.0, 0.0));
+        game.add_trail(ball, TrailComponent::new(4));
+        let empty = add_ball(&mut game, vec2(100.0, 0.0));
+        game.add_trail(empty, TrailComponent::new(0));
+
+        for tick in 1..=10 {
+            step(&mut game, 1);
+
+            let trail = &game.trail_container[ball];
+            assert_eq!(trail.positions.len(), tick.min(4));
+            assert_eq!(
+                trail.positions.back().copied(),
+                game.position(ball),
+                "the newest position comes last"
+            );
+            assert!(game.trail_container[empty].positions.is_empty());
+        }
+    }
+
+    /*
+        NOTE: Under gravity the distance grows with the square of the simulated time, so
+              the bodies fall at a constant speed to compare them one to one
+    */
+    #[test]
+    fn half_time_scale_falls_half_as_far() {
+        let fall = |time_scale: f32| {
+            let mut game = weightless();
+            game.set_time_scale(time_scale);
+
+            let ball = add_ball(&mut game, vec2(0.0, 0.0));
+            game.rigidbody_mut(ball)
+                .unwrap()
+                .set_linvel(vector![0.0, 100.0], true);
+
+            step(&mut game, 60);
+            game.position(ball).unwrap().y
+        };
+
+        assert!((fall(0.5) - fall(1.0) / 2.0).abs() < 1e-3);
+        assert_eq!(fall(0.0), 0.0);
+    }
+
+    #[test]
+    fn more_solver_iterations_sink_less_into_a_stack() {
+        // How far a heavy box rests inside the light box it is stacked on
+        let overlap = |iterations: usize| {
+            let mut game = Game::default();
+            game.set_solver_iterations(iterations, iterations, iterations);
+
+            let ground = game.new_entity("Ground");
+            game.add_physics(
+                ground,
+                RigidBodyBuilder::fixed()
+                    .translation(vector![0.0, 100.0])
+                    .build(),
+                ColliderBuilder::cuboid(200.0, 10.0).build(),
+            );
+
+            let [light, heavy] = [(80.0, 1.0), (60.0, 10.0)].map(|(y, density)| {
+                let entity = game.new_entity("Box");
+                game.add_physics(
+                    entity,
+                    RigidBodyBuilder::dynamic()
+                        .lock_rotations()
+                        .translation(vector![0.0, y])
+                        .build(),
+                    ColliderBuilder::cuboid(10.0, 10.0).density(density).build(),
+                );
+                entity
+            });
+
+            step(&mut game, 120);
+            20.0 - (game.position(light).unwrap().y - game.position(heavy).unwrap().y)
+        };
+
+        assert!(overlap(8) < overlap(1) / 2.0);
+    }
+
+    #[test]
+    fn falling_past_the_kill_plane_despawns_the_body() {
+        let mut game = Game {
+            kill_plane_y: Some(100.0),
+            ..Default::default()
+        };
+        let ball = add_ball(&mut game, vec2(0.0, 0.0));
+
+        let mut ticks = 0;
+        while game.is_alive(ball) {
+            assert!(game.position(ball).unwrap().y < 110.0, "despawned late");
+            step(&mut game, 1);
+
+            ticks += 1;
+            assert!(ticks < 600, "never despawned");
+        }
+
+        assert!(!game.rigidbody_container.contains_key(ball));
+        assert!(!game.collider_container.contains_key(ball));
+        assert_eq!(game.rigid_body_set.len(), 0);
+        assert_eq!(game.collider_set.len(), 0);
+    }
+
+    #[test]
+    fn disabled_sprites_are_not_drawn_and_do_not_fall() {
+        let mut game = Game::default();
+        // NOTE: Next to the camera's default target
+        let shown = add_sprite(&mut game, vec2(480.0, 500.0), vec2(10.0, 10.0));
+        let hidden = add_sprite(&mut game, vec2(520.0, 500.0), vec2(10.0, 10.0));
+        game.set_enabled(hidden, false);
+
+        step(&mut game, 30);
+
+        assert!(game.position(shown).unwrap().y > 500.0);
+        assert_eq!(game.position(hidden), Some(vec2(520.0, 500.0)));
+
+        let corner = game.position(shown).unwrap() - vec2(5.0, 5.0);
+        assert_eq!(drawn_sprites(&game), vec![(corner, vec2(10.0, 10.0))]);
+    }
+
+    #[test]
+    fn identical_simulations_hash_the_same_until_one_diverges() {
+        let scene = || {
+            let mut game = Game::with_seed(7);
+            add_ground(&mut game, 100.0);
+            let balls = (0..5)
+                .map(|i| add_ball(&mut game, vec2(i as f32 * 7.0, i as f32 * -12.0)))
+                .collect::<Vec<_>>();
+            (game, balls)
+        };
+
+        let (mut a, _) = scene();
+        let (mut b, balls) = scene();
+        for tick in 0..200 {
+            step(&mut a, 1);
+            step(&mut b, 1);
+            assert_eq!(a.state_hash(), b.state_hash(), "desync at tick {tick}");
+        }
+
+        b.apply_impulse(balls[2], vec2(50.0, 0.0));
+        step(&mut a, 1);
+        step(&mut b, 1);
+        assert_ne!(a.state_hash(), b.state_hash());
+    }
+
+    #[test]
+    fn linear_damping_slows_a_body_down_faster() {
+        let mut game = weightless();
+        let free = add_ball(&mut game, vec2(0.0, 0.0));
+        let damped = add_ball(&mut game, vec2(0.0, 100.0));
+        game.set_linear_damping(damped, 5.0);
+        for entity in [free, damped] {
+            game.set_linvel(entity, vec2(100.0, 0.0));
+        }
+
+        step(&mut game, 30);
+
+        let free = game.linvel(free).unwrap().x;
+        let damped = game.linvel(damped).unwrap().x;
+        assert!((free - 100.0).abs() < 1e-3);
+        assert!(damped < free / 2.0);
+    }
+
+    #[test]
+    fn bodies_sink_slower_inside_a_drag_zone() {
+        let mut game = Game::default();
+        let zone = game.new_entity("Water");
+        game.add_drag_zone(
+            zone,
+            DragZoneComponent {
+                linear_damping: 2.0,
+                buoyancy: 100.0,
+            },
+            ColliderBuilder::cuboid(50.0, 1_000.0).build(),
+        );
+        let inside = add_ball(&mut game, vec2(0.0, 0.0));
+        let outside = add_ball(&mut game, vec2(200.0, 0.0));
+
+        step(&mut game, 60);
+
+        let inside = game.position(inside).unwrap().y;
+        let outside = game.position(outside).unwrap().y;
+        assert!(inside > 0.0, "still sinks");
+        assert!(inside < outside / 2.0);
+    }
+
+    #[test]
+    fn a_run_of_solid_tiles_becomes_one_collider() {
+        let mut game = Game::default();
+        let mut tilemap = TilemapComponent::new(6, 4, 16.0, Texture2D::empty());
+        for x in 1..4 {
+            tilemap.set(x, 2, Some(0));
+        }
+        assert_eq!(tilemap.solid_rects(), vec![(1, 2, 3, 1)]);
+
+        let map = game.new_entity("Map");
+        game.add_tilemap(map, tilemap);
+        game.build_tilemap_colliders(map);
+        // NOTE: Rebuilding replaces the colliders instead of adding more
+        game.build_tilemap_colliders(map);
+
+        assert_eq!(game.collider_set.len(), 1);
+        let aabb = game.collider_set.iter().next().unwrap().1.compute_aabb();
+        assert_eq!(aabb.mins, point![16.0, 32.0]);
+        assert_eq!(aabb.maxs, point![64.0, 48.0]);
+    }
+
+    #[test]
+    fn dump_lists_every_entity_with_its_label_flags_and_position() {
+        let mut game = Game::default();
+        let ground = add_ground(&mut game, 100.0);
+        let ball = add_ball(&mut game, vec2(3.0, 4.0));
+        game.add_trail(ball, TrailComponent::new(2));
+        let marker = game.new_entity("Marker");
+
+        let physics = BitSet::empty()
+            .with(components::RIGIDBODY)
+            .with(components::COLLIDER);
+        assert_eq!(
+            game.dump(),
+            vec![
+                (ground, "Ground", physics, Some(vec2(0.0, 100.0))),
+                (
+                    ball,
+                    "Ball",
+                    physics.with(components::TRAIL),
+                    Some(vec2(3.0, 4.0))
+                ),
+                (marker, "Marker", BitSet::empty(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn wind_pushes_bodies_sideways_unless_their_drag_is_zero() {
+        let mut game = weightless();
+        game.set_wind(vec2(50.0, 0.0));
+        let blown = add_ball(&mut game, vec2(0.0, 0.0));
+        let sheltered = add_ball(&mut game, vec2(0.0, 100.0));
+        game.set_wind_drag(sheltered, 0.0);
+
+        step(&mut game, 60);
+
+        // NOTE: About half the wind's acceleration after a second
+        let drift = game.position(blown).unwrap();
+        assert!((20.0..30.0).contains(&drift.x), "{drift}");
+        assert_eq!(drift.y, 0.0);
+        assert_eq!(game.position(sheltered), Some(vec2(0.0, 100.0)));
+    }
+
+    #[test]
+    fn bodies_on_a_conveyor_drift_along_the_belt() {
+        let mut game = Game::default();
+        let belt = game.new_entity("Conveyor");
+        game.add_conveyor(
+            belt,
+            ConveyorComponent {
+                surface_velocity: vec2(-40.0, 0.0),
+            },
+            ColliderBuilder::cuboid(500.0, 10.0)
+                .translation(vector![0.0, 100.0])
+                .build(),
+        );
+        let ball = add_ball(&mut game, vec2(0.0, 85.0));
+
+        step(&mut game, 60);
+
+        let position = game.position(ball).unwrap();
+        assert!(position.x < -10.0, "{position}");
+        assert!((position.y - 85.0).abs() < 1.0, "stays on the belt");
+    }
+
+    #[test]
+    fn resting_on_the_ground_gives_an_upward_contact_normal() {
+        let mut game = Game::default();
+        let ground = add_ground(&mut game, 100.0);
+        let ball = add_ball(&mut game, vec2(0.0, 80.0));
+        let other = add_ball(&mut game, vec2(200.0, 0.0));
+
+        step(&mut game, 60);
+
+        // NOTE: Up is negative y
+        let normal = game.contact_normal(ball, ground).unwrap();
+        assert!(normal.y < -0.99, "{normal}");
+        let normal = game.contact_normal(ground, ball).unwrap();
+        assert!(normal.y > 0.99, "{normal}");
+        assert!(game.is_grounded(ball));
+
+        assert_eq!(game.contact_normal(ball, other), None);
+    }
+
+    /// A player touching a tall wall on its `side`, `-1.0` or `1.0` in world x.
+    fn player_at_wall(side: f32) -> (Game, Entity) {
+        let mut game = Game::default();
+        let wall = game.new_entity("Wall");
+        game.add_fixed_collider(
+            wall,
+            ColliderBuilder::cuboid(10.0, 1_000.0)
+                .translation(vector![side * 20.0, 0.0])
+                .build(),
+        );
+        let player = add_player(&mut game, vec2(0.0, 0.0));
+
+        (game, player)
+    }
+
+    #[test]
+    fn pressing_into_a_wall_slows_the_fall_on_either_side() {
+        for side in [-1.0, 1.0] {
+            let (mut sliding, player) = player_at_wall(side);
+            hold(&mut sliding, vec2(side, 0.0), 60);
+            let slide_speed = sliding.linvel(player).unwrap().y;
+
+            let (mut falling, player) = player_at_wall(side);
+            hold(&mut falling, Vec2::ZERO, 60);
+            let fall_speed = falling.linvel(player).unwrap().y;
+
+            // NOTE: The step after the clamp adds one tick of gravity
+            let tuning = PlayerTuning::default();
+            let gravity = sliding.gravity.y * DELTA;
+            assert!(slide_speed > 0.0 && slide_speed <= tuning.wall_slide_speed + gravity);
+            assert!(fall_speed > 2.0 * tuning.wall_slide_speed);
+        }
+    }
+
+    #[test]
+    fn wall_jumps_push_away_from_the_wall_on_either_side() {
+        for side in [-1.0, 1.0] {
+            let (mut game, player) = player_at_wall(side);
+            hold(&mut game, vec2(side, 0.0), 10);
+            assert_eq!(
+                game.wall_normal(player).map(|normal| normal.x.signum()),
+                Some(-side)
+            );
+
+            game.player_input.jump = true;
+            game.player_movement_system(DELTA);
+
+            let tuning = PlayerTuning::default();
+            assert_eq!(
+                game.linvel(player),
+                Some(vec2(-side, -1.0) * tuning.wall_jump_velocity)
+            );
+        }
+    }
+
+    #[test]
+    fn dashes_spike_the_velocity_and_wait_for_the_cooldown() {
+        let mut game = weightless();
+        let player = add_player(&mut game, vec2(0.0, 0.0));
+        game.set_linear_damping(player, 5.0);
+        let tuning = PlayerTuning::default();
+
+        game.player_input.dash = true;
+        step(&mut game, 1);
+        let dash = game.linvel(player).unwrap();
+        assert_eq!(dash.y, 0.0);
+        assert!(
+            dash.x < -0.9 * tuning.dash_speed,
+            "faces right, which is -x"
+        );
+        assert!(game.player_container[player].is_dashing());
+
+        hold(&mut game, Vec2::ZERO, 10);
+        let slowed = game.linvel(player).unwrap();
+        assert!(slowed.x > dash.x / 2.0);
+
+        game.player_input.dash = true;
+        step(&mut game, 1);
+        assert!(
+            game.linvel(player).unwrap().x > slowed.x,
+            "still cooling down"
+        );
+
+        hold(
+            &mut game,
+            Vec2::ZERO,
+            (tuning.dash_cooldown / DELTA) as usize,
+        );
+        game.player_input.dash = true;
+        game.player_input.movement = vec2(0.0, 1.0);
+        step(&mut game, 1);
+        assert!(game.linvel(player).unwrap().y > 0.9 * tuning.dash_speed);
+    }
+
+    #[test]
+    fn position_snapshots_round_trip_within_the_quantum() {
+        let scene = || {
+            let mut game = Game::default();
+            add_ground(&mut game, 100.0);
+            let trigger = game.new_entity("Trigger");
+            game.add_trigger(
+                trigger,
+                TriggerComponent { id: 1 },
+                ColliderBuilder::cuboid(20.0, 20.0).build(),
+            );
+            let balls = (0..3)
+                .map(|i| add_ball(&mut game, vec2(i as f32 * 123.37, -55.1)))
+                .collect::<Vec<_>>();
+            (game, balls)
+        };
+
+        let (mut sender, balls) = scene();
+        for (i, ball) in balls.iter().enumerate() {
+            sender.apply_impulse_at_point(*ball, vec2(300.0, 0.0), vec2(0.0, i as f32 * -55.1));
+        }
+        step(&mut sender, 30);
+        let bytes = sender.encode_positions();
+        assert_eq!(
+            bytes.len(),
+            4 + 3 * NET_RECORD_SIZE,
+            "only the dynamic balls"
+        );
+
+        let (mut receiver, _) = scene();
+        assert_eq!(receiver.decode_positions(&bytes), Some(3));
+
+        for ball in balls {
+            let sent = sender.rigidbody(ball).unwrap().position();
+            let received = receiver.rigidbody(ball).unwrap().position();
+
+            let error = sent.translation.vector - received.translation.vector;
+            assert!(error.abs().max() <= NET_POSITION_QUANTUM / 2.0);
+            let error = sent.rotation.angle() - received.rotation.angle();
+            assert!(error.abs() <= PI / i16::MAX as f32);
+        }
+
+        assert_eq!(receiver.decode_positions(&bytes[..bytes.len() - 1]), None);
+        assert_eq!(receiver.decode_positions(&[]), None);
+    }
+
+    #[test]
+    fn fast_players_stop_at_thin_walls() {
+        // Where a player running into a 2 units thick wall at x = -200 ends up
+        let run = |max_substeps: usize| {
+            let mut game = weightless();
+            game.player_tuning.max_substeps = max_substeps;
+
+            let wall = game.new_entity("Wall");
+            game.add_fixed_collider(
+                wall,
+                ColliderBuilder::cuboid(1.0, 100.0)
+                    .translation(vector![-200.0, 0.0])
+                    .build(),
+            );
+            let player = add_player(&mut game, vec2(0.0, 0.0));
+
+            for _ in 0..20 {
+                game.set_linvel(player, vec2(-3_000.0, 0.0));
+                step(&mut game, 1);
+            }
+
+            game.position(player).unwrap().x
+        };
+
+        let stopped = run(PlayerTuning::default().max_substeps);
+        assert!((stopped - -189.0).abs() < 1.0, "{stopped}");
+        assert!(run(1) < -201.0, "tunnels without substeps");
+    }
+
+    #[test]
+    fn extra_mass_weakens_an_impulse() {
+        let mut game = weightless();
+        let light = add_ball(&mut game, vec2(0.0, 0.0));
+        let heavy = add_ball(&mut game, vec2(0.0, 100.0));
+        let mass = game.mass(light).unwrap();
+        game.set_additional_mass(heavy, mass * 3.0);
+        assert!((game.mass(heavy).unwrap() - mass * 4.0).abs() < 1e-3);
+
+        for ball in [light, heavy] {
+            game.apply_impulse(ball, vec2(mass * 40.0, 0.0));
+        }
+        step(&mut game, 1);
+
+        assert!((game.linvel(light).unwrap().x - 40.0).abs() < 1e-3);
+        assert!((game.linvel(heavy).unwrap().x - 10.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn moving_the_center_of_mass_keeps_the_mass() {
+        let mut game = weightless();
+        let ball = add_ball(&mut game, vec2(0.0, 0.0));
+        let mass = game.mass(ball).unwrap();
+        assert_eq!(game.center_of_mass(ball), Some(Vec2::ZERO));
+
+        game.set_center_of_mass(ball, vec2(2.0, -1.0));
+
+        assert_eq!(game.center_of_mass(ball), Some(vec2(2.0, -1.0)));
+        assert!((game.mass(ball).unwrap() - mass).abs() < 1e-3);
+    }
+
+    #[test]
+    fn torque_impulses_spin_bodies_up() {
+        let mut game = weightless();
+        let ball = add_ball(&mut game, vec2(0.0, 0.0));
+        assert_eq!(game.angvel(ball), Some(0.0));
+
+        game.apply_torque_impulse(ball, 5_000.0);
+        step(&mut game, 1);
+        assert!(game.angvel(ball).unwrap() > 0.0);
+
+        game.apply_torque_impulse(ball, -10_000.0);
+        step(&mut game, 1);
+        assert!(game.angvel(ball).unwrap() < 0.0);
+    }
+
+    #[test]
+    fn locked_axes_ignore_impulses_along_them() {
+        let mut game = weightless();
+        let ball = add_ball(&mut game, vec2(0.0, 0.0));
+        game.lock_translation_x(ball, true);
+
+        game.apply_impulse(ball, vec2(1_000.0, 1_000.0));
+        step(&mut game, 30);
+        let position = game.position(ball).unwrap();
+        assert_eq!(position.x, 0.0);
+        assert!(position.y > 0.0);
+
+        game.lock_translation_x(ball, false);
+        game.apply_impulse(ball, vec2(1_000.0, 0.0));
+        step(&mut game, 30);
+        assert!(game.position(ball).unwrap().x > 0.0);
+    }
+
+    #[test]
+    fn points_inside_a_triangle_collider_find_it() {
+        let mut game = Game::default();
+        let triangle = game.new_entity("Triangle");
+        let corners = [vec2(0.0, 0.0), vec2(100.0, 0.0), vec2(0.0, 100.0)];
+        game.add_convex_collider(triangle, &corners).unwrap();
+
+        assert_eq!(game.entity_at_point(vec2(20.0, 20.0)), Some(triangle));
+        assert_eq!(game.entity_at_point(vec2(80.0, 80.0)), None);
+
+        let line = game.new_entity("Line");
+        assert_eq!(
+            game.add_convex_collider(line, &[vec2(0.0, 0.0), vec2(1.0, 1.0), vec2(2.0, 2.0)]),
+            Err(ShapeError::DegenerateConvexHull)
+        );
+        assert!(!game.has_flag(line, components::COLLIDER));
+    }
+
+    #[test]
+    fn balls_roll_down_a_sloped_polyline() {
+        let mut game = Game::default();
+        let slope = game.new_entity("Slope");
+        // NOTE: Down is positive y, so this falls off to the right
+        game.add_polyline_collider(slope, &[vec2(-200.0, 0.0), vec2(200.0, 200.0)])
+            .unwrap();
+        let ball = add_ball(&mut game, vec2(0.0, 80.0));
+
+        step(&mut game, 60);
+
+        let position = game.position(ball).unwrap();
+        assert!(position.x > 20.0, "{position}");
+        assert!(
+            position.y < position.x / 2.0 + 100.0,
+            "stays on top of the slope"
+        );
+
+        let point = game.new_entity("Point");
+        assert_eq!(
+            game.add_polyline_collider(point, &[Vec2::ZERO]),
+            Err(ShapeError::TooFewPoints { got: 1, needed: 2 })
+        );
+    }
+
+    #[test]
+    fn compound_bodies_collide_with_every_shape() {
+        let mut game = Game::default();
+        let platform = game.new_entity("Platform");
+        game.add_fixed_collider(
+            platform,
+            ColliderBuilder::cuboid(10.0, 10.0)
+                .translation(vector![0.0, 100.0])
+                .build(),
+        );
+        game.add_extra_collider(
+            platform,
+            ColliderBuilder::cuboid(10.0, 10.0).build(),
+            vec2(40.0, 100.0),
+        )
+        .unwrap();
+        let on_main = add_ball(&mut game, vec2(0.0, 50.0));
+        let on_extra = add_ball(&mut game, vec2(40.0, 50.0));
+
+        step(&mut game, 120);
+
+        for ball in [on_main, on_extra] {
+            assert!((game.position(ball).unwrap().y - 85.0).abs() < 0.5);
+            assert!(game.contact_normal(ball, platform).is_some());
+        }
+        assert_eq!(game.entity_at_point(vec2(45.0, 100.0)), Some(platform));
+    }
+
+    #[test]
+    fn despawning_frees_every_collider_of_the_entity() {
+        let mut game = Game::default();
+        let entity = add_ball(&mut game, vec2(0.0, 0.0));
+        game.add_extra_collider(entity, ColliderBuilder::ball(5.0).build(), vec2(10.0, 0.0))
+            .unwrap();
+        let handles = game.colliders_of(entity).to_vec();
+        assert_eq!(handles.len(), 2);
+
+        game.commands.despawn(entity);
+        game.flush_commands();
+        game.flush_despawns();
+
+        assert!(handles
+            .iter()
+            .all(|handle| !game.collider_set.contains(*handle)));
+        assert_eq!(game.collider_set.len(), 0);
+        assert!(game.colliders_of(entity).is_empty());
+    }
+
+    /// Whether a player dropped onto ground falling `slope` units down per unit right
+    /// counts as grounded, just after the drop and once it has landed.
+    fn grounded_on_slope(slope: f32, feet_sensor: bool) -> (bool, bool) {
+        let mut game = Game::default();
+        let ground = game.new_entity("Ground");
+        game.add_polyline_collider(
+            ground,
+            &[
+                vec2(-400.0, 100.0 - 400.0 * slope),
+                vec2(400.0, 100.0 + 400.0 * slope),
+            ],
+        )
+        .unwrap();
+        let player = add_player(&mut game, vec2(0.0, 40.0));
+        if feet_sensor {
+            game.add_feet_sensor(player, 8.0, 16.0).unwrap();
+        }
+
+        step(&mut game, 1);
+        let dropped = game.player_grounded(player);
+        step(&mut game, 60);
+        (dropped, game.player_grounded(player))
+    }
+
+    #[test]
+    fn feet_sensors_keep_players_grounded_on_steep_slopes() {
+        assert_eq!(grounded_on_slope(0.0, false), (false, true));
+        assert_eq!(grounded_on_slope(0.0, true), (false, true));
+
+        // NOTE: Steeper than `SURFACE_NORMAL_THRESHOLD`, so the contact normal doesn't count
+        assert_eq!(grounded_on_slope(1.4, false), (false, false));
+        assert_eq!(grounded_on_slope(1.4, true), (false, true));
+    }
+
+    #[test]
+    fn nearest_entity_is_the_closest_matching_one() {
+        let mut game = weightless();
+        assert_eq!(game.nearest_entity(Vec2::ZERO, None), None);
+
+        let [player, closest, _far] =
+            [100.0, -30.0, 60.0].map(|x| add_ball(&mut game, vec2(x, 0.0)));
+        game.add_player_component(player, PlayerComponent::default());
+
+        // NOTE: Measured to the edge of the ball, not its center
+        assert_eq!(game.nearest_entity(Vec2::ZERO, None), Some((closest, 25.0)));
+        assert_eq!(
+            game.nearest_entity(Vec2::ZERO, Some(Query::new(components::PLAYER))),
+            Some((player, 95.0))
+        );
+        assert_eq!(
+            game.nearest_entity(Vec2::ZERO, Some(Query::new(components::TRIGGER))),
+            None
+        );
+
+        game.set_enabled(closest, false);
+        assert_ne!(game.nearest_entity(Vec2::ZERO, None).unwrap().0, closest);
+    }
+
+    #[test]
+    fn homing_bodies_turn_towards_their_target() {
+        let mut game = weightless();
+        let target = game.new_entity("Target");
+        game.add_fixed_collider(
+            target,
+            ColliderBuilder::ball(5.0)
+                .translation(vector![0.0, 500.0])
+                .build(),
+        );
+        game.add_player_component(target, PlayerComponent::default());
+
+        let missile = game.new_entity("Missile");
+        game.add_physics(
+            missile,
+            RigidBodyBuilder::dynamic().build(),
+            ColliderBuilder::ball(1.0).sensor(true).build(),
+        );
+        game.set_linvel(missile, vec2(100.0, 0.0));
+        let homing = HomingComponent {
+            turn_rate: 1.0,
+            speed: 200.0,
+            target: None,
+            targets: Query::new(components::PLAYER),
+        };
+        game.add_homing(missile, homing);
+
+        let heading = |game: &Game| {
+            let velocity = game.linvel(missile).unwrap();
+            velocity.y.atan2(velocity.x)
+        };
+
+        // NOTE: The target is straight down, at a heading of pi / 2
+        let mut previous = heading(&game);
+        for _ in 0..30 {
+            step(&mut game, 1);
+
+            let heading = heading(&game);
+            assert!(heading > previous && heading <= PI / 2.0);
+            assert!(
+                (heading - previous - DELTA).abs() < 1e-4,
+                "turns at `turn_rate`"
+            );
+            previous = heading;
+        }
+
+        assert_eq!(game.homing_container[missile].target, Some(target));
+        assert!((game.linvel(missile).unwrap().length() - 200.0).abs() < 1e-3);
+    }
+
+    #[test]
+    fn presets_set_their_integration_parameters() {
+        let game = Game::with_physics_preset(PhysicsPreset::Accurate);
+        let parameters = &game.integration_parameters;
+        assert_eq!(parameters.max_velocity_iterations, 8);
+        assert_eq!(parameters.max_velocity_friction_iterations, 16);
+        assert_eq!(parameters.max_stabilization_iterations, 2);
+        assert_eq!(parameters.max_ccd_substeps, 4);
+
+        let mut game = Game::with_physics_preset(PhysicsPreset::Fast);
+        game.set_solver_iterations(3, 0, 5);
+        let parameters = &game.integration_parameters;
+        assert_eq!(parameters.max_velocity_iterations, 3);
+        assert_eq!(parameters.max_velocity_friction_iterations, 1, "clamped");
+        assert_eq!(parameters.max_stabilization_iterations, 5);
+        assert_eq!(parameters.max_ccd_substeps, 1, "kept from the preset");
+
+        let stable = PhysicsPreset::Stable.integration_parameters();
+        let rapier = IntegrationParameters::default();
+        assert_eq!(stable.dt, GOAL_DELTA_TIME as f32);
+        assert_eq!(
+            stable.max_velocity_iterations,
+            rapier.max_velocity_iterations
+        );
+        assert_eq!(stable.max_ccd_substeps, rapier.max_ccd_substeps);
+    }
+
+    #[test]
+    fn dying_and_leaving_the_bounds_in_one_tick_costs_one_life() {
+        let mut game = Game::default();
+        let player = add_player(&mut game, vec2(500.0, 500.0));
+
+        game.events.push(GameEvent::Died { entity: player });
+        game.events.push(GameEvent::OutOfBounds { entity: player });
+        game.respawn_system();
+
+        assert_eq!(game.lives(), Resources::default().lives - 1);
+    }
+
+    #[test]
+    fn the_same_seed_generates_the_same_level() {
+        fn layout(seed: u64) -> Vec<[f32; 4]> {
+            let mut game = Game::default();
+            let bounds = Rect::new(0.0, 0.0, 1000.0, 600.0);
+
+            game.generate_random_level(seed, 8, 20, bounds)
+                .into_iter()
+                .map(|entity| {
+                    let handle = game
+                        .collider_container
+                        .get(entity)
+                        .unwrap()
+                        .collider_handle();
+                    let aabb = game.collider_set[handle].compute_aabb();
+                    [aabb.mins.x, aabb.mins.y, aabb.maxs.x, aabb.maxs.y]
+                })
+                .collect()
+        }
+
+        let level = layout(3);
+        assert_eq!(level.len(), 28);
+        assert_eq!(level, layout(3));
+        assert_ne!(level, layout(4));
+
+        let platforms = &level[..8];
+        for (i, a) in platforms.iter().enumerate() {
+            assert!(a[0] >= 0.0 && a[1] >= 0.0 && a[2] <= 1000.0 && a[3] <= 600.0);
+
+            for b in &platforms[i + 1..] {
+                let overlap = a[0] < b[2] && b[0] < a[2] && a[1] < b[3] && b[1] < a[3];
+                assert!(!overlap, "{a:?} overlaps {b:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn teleported_entities_render_where_they_landed_at_any_alpha() {
+        let mut game = weightless();
+        let ball = add_ball(&mut game, vec2(0.0, 0.0));
+        step(&mut game, 1);
+
+        game.set_position(ball, vec2(100.0, 0.0));
+        let (halfway, _rotation) = game.interpolated_position(ball, 0.5).unwrap();
+        assert!(halfway.abs_diff_eq(vec2(50.0, 0.0), 1e-4), "{halfway}");
+
+        game.set_position_teleport(ball, vec2(300.0, -40.0));
+        for alpha in [0.0, 0.25, 0.5, 1.0] {
+            let (position, _rotation) = game.interpolated_position(ball, alpha).unwrap();
+            assert!(position.abs_diff_eq(vec2(300.0, -40.0), 1e-4), "{position}");
+        }
+    }
+
+    #[test]
+    fn aabb_queries_return_the_entities_poking_into_the_box() {
+        let mut game = weightless();
+        let inside = add_ball(&mut game, vec2(50.0, 50.0));
+        let poking_in = add_ball(&mut game, vec2(103.0, 50.0));
+        let touching = add_ball(&mut game, vec2(50.0, -5.0));
+        let _outside = add_ball(&mut game, vec2(110.0, 50.0));
+        // NOTE: 5.66 away from the corner, so only its bounding box would overlap
+        let _past_the_corner = add_ball(&mut game, vec2(-4.0, -4.0));
+
+        let mut found = game.entities_in_aabb(vec2(100.0, 100.0), vec2(0.0, 0.0));
+        found.sort();
+        assert_eq!(found, vec![inside, poking_in, touching]);
+    }
+
+    #[test]
+    fn resting_bodies_are_in_contact_with_the_ground() {
+        let mut game = Game::default();
+        let ground = add_ground(&mut game, 100.0);
+        let ball = add_ball(&mut game, vec2(0.0, 80.0));
+        let far_away = add_ball(&mut game, vec2(300.0, -500.0));
+        step(&mut game, 60);
+
+        assert_eq!(game.contacts_of(ball), vec![ground]);
+        assert_eq!(game.contacts_of(ground), vec![ball]);
+        assert!(game.contacts_of(far_away).is_empty());
+    }
+
+    #[test]
+    fn absurd_velocities_are_clamped_and_broken_ones_zeroed() {
+        let mut game = weightless();
+        game.set_max_speed(Some(100.0));
+        let fast = add_ball(&mut game, vec2(0.0, 0.0));
+        let broken = add_ball(&mut game, vec2(200.0, 0.0));
+
+        game.set_linvel(fast, vec2(3e5, -4e5));
+        game.set_linvel(broken, vec2(f32::NAN, 1.0));
+        let events = step(&mut game, 1);
+
+        let linvel = game.linvel(fast).unwrap();
+        assert!(linvel.abs_diff_eq(vec2(60.0, -80.0), 1e-3), "{linvel}");
+        assert!(game.position(fast).unwrap().length() < 100.0 * DELTA + 1e-3);
+
+        assert_eq!(game.linvel(broken), Some(Vec2::ZERO));
+        assert_eq!(game.position(broken), Some(vec2(200.0, 0.0)));
+        assert!(events.contains(&GameEvent::NonFiniteVelocity { entity: broken }));
+        assert!(!events.contains(&GameEvent::NonFiniteVelocity { entity: fast }));
+    }
+
+    #[test]
+    fn scaling_a_ball_scales_its_radius_mass_and_sprite() {
+        let mut game = weightless();
+        let ball = add_sprite(&mut game, vec2(0.0, 0.0), vec2(10.0, 10.0));
+        let mass = game.mass(ball).unwrap();
+        assert_eq!(game.entity_at_point(vec2(8.0, 0.0)), None);
+
+        game.set_collider_scale(ball, vec2(2.0, 2.0));
+        assert_eq!(game.entity_at_point(vec2(8.0, 0.0)), Some(ball));
+        assert_eq!(game.entity_at_point(vec2(11.0, 0.0)), None);
+        assert!((game.mass(ball).unwrap() - mass * 4.0).abs() < 1e-3);
+        assert_eq!(game.texture_container[ball].size, vec2(20.0, 20.0));
+
+        // NOTE: Scales are relative to the original size, not the current one
+        game.set_collider_scale(ball, vec2(1.0, 1.0));
+        assert_eq!(game.entity_at_point(vec2(8.0, 0.0)), None);
+        assert_eq!(game.texture_container[ball].size, vec2(10.0, 10.0));
+    }
+
+    #[test]
+    fn the_camera_follows_the_chosen_target_instead_of_the_player() {
+        let mut game = weightless();
+        let player = add_player(&mut game, vec2(500.0, 500.0));
+        let ball = add_ball(&mut game, vec2(900.0, 500.0));
+        assert_eq!(game.camera_target(), Some(player));
+
+        game.set_camera_target(Some(ball));
+        game.camera_follow_system(DELTA);
+        let first = game.camera.target.x;
+        assert!(first > 500.0, "{first}");
+
+        for _ in 0..120 {
+            game.camera_follow_system(DELTA);
+        }
+        let dead_zone = game.camera_tuning.dead_zone;
+        assert!((game.camera.target.x - (900.0 - dead_zone.x)).abs() < 1e-2);
+        assert_eq!(game.camera.target.y, 500.0);
+
+        game.remove_entity(ball);
+        assert_eq!(game.camera_target(), Some(player));
+    }
+
+    #[test]
+    fn the_camera_ignores_moves_inside_the_dead_zone() {
+        let mut game = weightless();
+        let ball = add_ball(&mut game, vec2(500.0, 500.0) + vec2(25.0, -45.0));
+        game.set_camera_target(Some(ball));
+
+        for _ in 0..60 {
+            game.camera_follow_system(DELTA);
+        }
+        assert_eq!(game.camera.target, vec2(500.0, 500.0));
+    }
+
+    #[test]
+    fn the_camera_looks_ahead_along_the_velocity() {
+        let look_ahead = |velocity: Vec2| {
+            let mut game = weightless();
+            let ball = add_ball(&mut game, vec2(500.0, 500.0));
+            game.set_camera_target(Some(ball));
+            game.set_linvel(ball, velocity);
+
+            for _ in 0..600 {
+                game.camera_follow_system(DELTA);
+            }
+            game.camera.target - vec2(500.0, 500.0)
+        };
+
+        // NOTE: 0.2 seconds ahead, less the dead zone the focus may stay in
+        let ahead = look_ahead(vec2(500.0, 0.0));
+        assert!(ahead.abs_diff_eq(vec2(100.0 - 30.0, 0.0), 1e-2), "{ahead}");
+
+        let ahead = look_ahead(vec2(0.0, -5_000.0));
+        assert!(
+            ahead.abs_diff_eq(vec2(0.0, -150.0 + 50.0), 1e-2),
+            "capped, {ahead}"
+        );
+    }
+
+    #[test]
+    fn a_render_scale_of_two_doubles_the_drawn_size_around_the_center() {
+        let mut game = weightless();
+        let sprite = add_sprite(&mut game, vec2(500.0, 500.0), vec2(10.0, 20.0));
+        assert_eq!(
+            drawn_sprites(&game),
+            vec![(vec2(495.0, 490.0), vec2(10.0, 20.0))]
+        );
+
+        game.texture_container[sprite].render_scale = vec2(2.0, 2.0);
+        assert_eq!(
+            drawn_sprites(&game),
+            vec![(vec2(490.0, 480.0), vec2(20.0, 40.0))]
+        );
+        assert_eq!(game.texture_container[sprite].size, vec2(10.0, 20.0));
+    }
+
+    #[test]
+    fn landing_squashes_the_player_then_eases_back() {
+        let mut game = Game::default();
+        add_ground(&mut game, 100.0);
+        let player = add_player(&mut game, vec2(0.0, 20.0));
+        game.add_texture(player, super::tests::texture(vec2(20.0, 40.0)));
+
+        let mut scales = vec![];
+        for _ in 0..90 {
+            step(&mut game, 1);
+            scales.push(game.texture_container[player].render_scale);
+        }
+
+        let landed = scales.iter().position(|scale| *scale != Vec2::ONE).unwrap();
+        assert!(landed > 0, "airborne until it lands");
+
+        let squash = game.player_tuning.land_squash;
+        let squashed = scales[landed];
+        assert!(squashed.x > 1.0 && squashed.y < 1.0, "{squashed}");
+        assert!(squashed.abs_diff_eq(squash, 0.1), "{squashed}");
+
+        let ticks = (game.player_tuning.squash_duration / DELTA).ceil() as usize;
+        assert!(scales[landed..=landed + ticks]
+            .windows(2)
+            .all(|w| w[1].y >= w[0].y));
+        assert_eq!(
+            scales[landed + ticks + 1..],
+            vec![Vec2::ONE; 90 - landed - ticks - 1]
+        );
+    }
+
+    #[test]
+    fn sprites_are_drawn_with_the_color_and_size_they_were_set_to() {
+        let mut game = weightless();
+        let sprite = add_sprite(&mut game, vec2(500.0, 500.0), vec2(10.0, 10.0));
+        let plain = add_ball(&mut game, vec2(500.0, 500.0));
+
+        game.set_texture_color(sprite, RED);
+        game.set_texture_size(sprite, vec2(4.0, 6.0));
+        game.set_texture_color(plain, RED);
+        assert!(!game.texture_container.contains_key(plain));
+
+        let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+        game.render_sprites_system(&mut renderer);
+
+        match &renderer.calls[..] {
+            [DrawCall::Texture {
+                color, dest_size, ..
+            }] => {
+                assert_eq!(*color, RED);
+                assert_eq!(*dest_size, Some(vec2(4.0, 6.0)));
+            }
+            calls => panic!("expected one sprite, found {calls:?}"),
+        }
+    }
+
+    #[test]
+    fn sprites_are_drawn_at_the_body_rotation_plus_their_offset() {
+        let mut game = weightless();
+        let sprite = add_sprite(&mut game, vec2(500.0, 500.0), vec2(10.0, 10.0));
+        game.rigidbody_mut(sprite).unwrap().set_rotation(0.5, true);
+        game.reset_interpolation(sprite);
+
+        let rotations = |game: &Game| {
+            let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+            game.render_sprites_system(&mut renderer);
+
+            renderer
+                .calls
+                .iter()
+                .map(|call| match call {
+                    DrawCall::Texture { rotation, .. } => *rotation,
+                    call => panic!("sprites are textures, found {call:?}"),
+                })
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(rotations(&game), vec![0.5]);
+
+        game.texture_container[sprite].rotation_offset = -std::f32::consts::FRAC_PI_2;
+        let drawn = rotations(&game)[0];
+        assert!(
+            (drawn - (0.5 - std::f32::consts::FRAC_PI_2)).abs() < 1e-6,
+            "{drawn}"
+        );
+    }
+
+    #[test]
+    fn cloned_worlds_step_independently() {
+        let mut game = Game::default();
+        add_ground(&mut game, 100.0);
+        let balls =
+            [vec2(0.0, 0.0), vec2(50.0, -40.0)].map(|position| add_ball(&mut game, position));
+        game.set_linvel(balls[1], vec2(30.0, 0.0));
+        step(&mut game, 10);
+
+        let mut clone = game.clone_world();
+        assert_eq!(clone.entities.len(), game.entities.len());
+        for ball in balls {
+            assert_eq!(clone.position(ball), game.position(ball));
+            assert_eq!(clone.linvel(ball), game.linvel(ball));
+        }
+
+        let before = balls.map(|ball| game.position(ball).unwrap());
+        step(&mut clone, 30);
+        assert_eq!(balls.map(|ball| game.position(ball).unwrap()), before);
+        assert_ne!(balls.map(|ball| clone.position(ball).unwrap()), before);
+
+        clone.remove_entity(balls[0]);
+        assert!(game.is_alive(balls[0]));
+        assert_eq!(game.rigid_body_set.len(), 3);
+    }
+
+    #[test]
+    fn physics_entities_hands_out_the_bodies_and_colliders_to_mutate() {
+        let mut game = weightless();
+        let a = add_ball(&mut game, vec2(0.0, 0.0));
+        let b = add_ball(&mut game, vec2(100.0, 0.0));
+        let disabled = add_ball(&mut game, vec2(200.0, 0.0));
+        game.set_enabled(disabled, false);
+
+        let mut seen = vec![];
+        game.physics_entities(|entity, rigidbody, collider| {
+            rigidbody.set_linvel(vector![3.0, 4.0], true);
+            collider.set_friction(0.25);
+            seen.push(entity);
+        });
+
+        assert_eq!(seen, vec![a, b]);
+        assert_eq!(game.linvel(a), Some(vec2(3.0, 4.0)));
+        assert_eq!(game.linvel(b), Some(vec2(3.0, 4.0)));
+        assert_eq!(game.linvel(disabled), Some(Vec2::ZERO));
+
+        let friction = |entity| {
+            let handle = game.collider_container[entity].collider_handle();
+            game.collider_set[handle].friction()
+        };
+        assert_eq!(friction(b), 0.25);
+        assert_ne!(friction(disabled), 0.25);
+    }
+
+    #[test]
+    fn combine_rules_decide_how_high_a_ball_bounces() {
+        let bounce = |rule: CoefficientCombineRule| {
+            let mut game = Game::default();
+            let ground = add_ground(&mut game, 100.0);
+            let ball = add_ball(&mut game, vec2(0.0, -100.0));
+
+            for (entity, restitution) in [(ground, 0.2), (ball, 0.8)] {
+                game.add_material(
+                    entity,
+                    Material {
+                        restitution,
+                        restitution_combine: rule,
+                        ..Default::default()
+                    },
+                );
+            }
+
+            let mut falling = true;
+            let mut highest = f32::MAX;
+            for _ in 0..120 {
+                step(&mut game, 1);
+                let linvel = game.linvel(ball).unwrap();
+
+                if falling && linvel.y < 0.0 {
+                    falling = false;
+                }
+                if !falling {
+                    highest = highest.min(game.position(ball).unwrap().y);
+                }
+            }
+
+            // NOTE: Resting on the ground puts the ball's center at 85
+            85.0 - highest
+        };
+
+        let min = bounce(CoefficientCombineRule::Min);
+        let average = bounce(CoefficientCombineRule::Average);
+        let max = bounce(CoefficientCombineRule::Max);
+
+        assert!(min < average && average < max, "{min}, {average}, {max}");
+        assert!(max > 185.0 * 0.8 * 0.8 * 0.8, "{max}");
+    }
+
+    #[test]
+    fn bodies_fall_through_platforms_while_their_collider_is_disabled() {
+        let mut game = Game::default();
+        add_ground(&mut game, 100.0);
+        let ball = add_ball(&mut game, vec2(0.0, 60.0));
+        step(&mut game, 60);
+        let resting = game.position(ball).unwrap().y;
+        assert!((resting - 85.0).abs() < 1.0, "{resting}");
+
+        game.set_collider_enabled(ball, false);
+        step(&mut game, 60);
+        let fallen = game.position(ball).unwrap().y;
+        assert!(fallen > 200.0, "{fallen}");
+
+        // NOTE: Dropped back onto the platform from above, it is solid again
+        game.set_position_teleport(ball, vec2(0.0, 60.0));
+        game.set_linvel(ball, Vec2::ZERO);
+        game.set_collider_enabled(ball, true);
+        step(&mut game, 60);
+        assert!((game.position(ball).unwrap().y - resting).abs() < 1.0);
+    }
+
+    #[test]
+    fn gravity_scales_keep_bodies_up_or_float_them() {
+        let mut game = Game::default();
+        let normal = add_ball(&mut game, vec2(0.0, 0.0));
+        let weightless = add_ball(&mut game, vec2(100.0, 0.0));
+        let balloon = add_ball(&mut game, vec2(200.0, 0.0));
+        game.set_gravity_scale(weightless, 0.0);
+        game.set_gravity_scale(balloon, -0.5);
+        step(&mut game, 30);
+
+        assert!(game.position(normal).unwrap().y > 10.0);
+        assert_eq!(game.position(weightless), Some(vec2(100.0, 0.0)));
+        assert_eq!(game.linvel(weightless), Some(Vec2::ZERO));
+
+        let floated = game.position(balloon).unwrap().y;
+        let fell = game.position(normal).unwrap().y;
+        assert!((floated + fell * 0.5).abs() < 1e-2, "{floated} vs {fell}");
+    }
+
+    #[test]
+    fn off_center_impulses_spin_bodies_and_centered_ones_dont() {
+        let mut game = weightless();
+        let centered = add_ball(&mut game, vec2(0.0, 0.0));
+        let off_center = add_ball(&mut game, vec2(100.0, 0.0));
+
+        game.apply_impulse_at_point(centered, vec2(0.0, -50.0), vec2(0.0, 0.0));
+        game.apply_impulse_at_point(off_center, vec2(0.0, -50.0), vec2(104.0, 0.0));
+
+        let linvel = game.linvel(centered).unwrap();
+        assert!(linvel.y < 0.0 && linvel.x == 0.0, "{linvel}");
+        assert_eq!(game.angvel(centered), Some(0.0));
+
+        // NOTE: The same push on the same mass, only the spin differs
+        assert_eq!(game.linvel(off_center), Some(linvel));
+        assert!(game.angvel(off_center).unwrap() < 0.0);
+    }
+
+    #[test]
+    fn physics_stats_count_the_touching_bodies() {
+        let mut game = Game::default();
+        step(&mut game, 1);
+        assert_eq!(game.physics_stats(), PhysicsStats::default());
+
+        add_ground(&mut game, 100.0);
+        add_ball(&mut game, vec2(0.0, 80.0));
+        add_ball(&mut game, vec2(100.0, 80.0));
+        add_ball(&mut game, vec2(300.0, -300.0));
+
+        let trigger = game.new_entity("Trigger");
+        game.add_trigger(
+            trigger,
+            TriggerComponent { id: 1 },
+            ColliderBuilder::cuboid(20.0, 20.0)
+                .translation(vector![100.0, 80.0])
+                .build(),
+        );
+        step(&mut game, 10);
+
+        let stats = game.physics_stats();
+        assert_eq!(stats.active_bodies, 3);
+        assert_eq!(stats.contact_pairs, 2);
+        assert_eq!(stats.intersection_pairs, 1);
+        assert!(stats.broad_phase_pairs >= 3, "{stats:?}");
+    }
+
+    #[test]
+    fn drawing_n_sprites_counts_n_sprites_drawn() {
+        let mut game = weightless();
+        for i in 0..5 {
+            add_sprite(
+                &mut game,
+                vec2(450.0 + i as f32 * 20.0, 500.0),
+                vec2(10.0, 10.0),
+            );
+        }
+        for far_away in [vec2(-5_000.0, 500.0), vec2(500.0, 5_000.0)] {
+            add_sprite(&mut game, far_away, vec2(10.0, 10.0));
+        }
+
+        let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+        game.render_sprites_system(&mut renderer);
+        assert_eq!(renderer.calls.len(), 5);
+        assert_eq!(
+            game.render_stats(),
+            RenderStats {
+                draw_calls: 5,
+                sprites_drawn: 5,
+                sprites_culled: 2,
+            }
+        );
+
+        game.render_sprites_system(&mut renderer);
+        assert_eq!(game.render_stats().sprites_drawn, 10);
+    }
+
+    #[test]
+    fn render_sprites_system_emits_one_draw_call_per_sprite() {
+        let mut game = weightless();
+        let plain = add_sprite(&mut game, vec2(450.0, 500.0), vec2(10.0, 20.0));
+        let tinted = add_sprite(&mut game, vec2(550.0, 480.0), vec2(8.0, 8.0));
+        game.set_texture_color(tinted, BLUE);
+        game.rigidbody_mut(tinted).unwrap().set_rotation(1.0, true);
+        game.reset_interpolation(tinted);
+
+        let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+        game.render_sprites_system(&mut renderer);
+
+        let texture = |entity, position, color, dest_size, rotation| DrawCall::Texture {
+            texture: game.texture_container[entity].texture,
+            position,
+            color,
+            dest_size: Some(dest_size),
+            source: None,
+            rotation,
+            flip_x: false,
+            flip_y: false,
+            pivot: None,
+        };
+        assert_eq!(
+            renderer.calls,
+            vec![
+                texture(plain, vec2(445.0, 490.0), WHITE, vec2(10.0, 20.0), 0.0),
+                texture(tinted, vec2(546.0, 476.0), BLUE, vec2(8.0, 8.0), 1.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn shapes_spawn_at_the_world_position_under_the_screen_point() {
+        let screen_size = vec2(800.0, 600.0);
+        let mut game = Game {
+            // NOTE: One pixel per world unit
+            camera: Camera2D {
+                target: vec2(100.0, 200.0),
+                zoom: vec2(2.0 / 800.0, 2.0 / 600.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let center = game
+            .spawn_at_screen_point(SpawnShape::Ball, vec2(400.0, 300.0), screen_size)
+            .unwrap();
+        assert_eq!(game.position(center), Some(vec2(100.0, 200.0)));
+
+        let point = vec2(600.0, 150.0);
+        let corner = game
+            .spawn_at_screen_point(SpawnShape::Box, point, screen_size)
+            .unwrap();
+        let position = game.position(corner).unwrap();
+        assert!((position.x - 300.0).abs() < 1e-3, "{position}");
+        // NOTE: A positive zoom points world space y up the screen, unlike the game's camera
+        assert!((position.y - 350.0).abs() < 1e-3, "{position}");
+        assert!(world_to_screen(&game.camera, position, screen_size).abs_diff_eq(point, 1e-3));
+
+        assert_eq!(game.label_container[corner], "Box");
+    }
+
+    #[test]
+    fn the_default_material_only_changes_later_spawns() {
+        fn bounce(game: &mut Game, x: f32) -> (Entity, f32) {
+            let ball = game.spawn_shape(SpawnShape::Ball, vec2(x, -100.0)).unwrap();
+
+            let mut falling = true;
+            let mut highest = f32::MAX;
+            for _ in 0..120 {
+                step(game, 1);
+                falling &= game.linvel(ball).unwrap().y >= 0.0;
+                if !falling {
+                    highest = highest.min(game.position(ball).unwrap().y);
+                }
+            }
+
+            (ball, 85.0 - highest)
+        }
+
+        let mut game = Game::default();
+        add_ground(&mut game, 100.0);
+
+        let (dull, dull_bounce) = bounce(&mut game, 0.0);
+
+        game.set_default_material(Material {
+            restitution: 0.8,
+            restitution_combine: CoefficientCombineRule::Max,
+            ..Default::default()
+        });
+        let (_bouncy, bouncy_bounce) = bounce(&mut game, 200.0);
+
+        assert!(dull_bounce < 1.0, "{dull_bounce}");
+        assert!(bouncy_bounce > 50.0, "{bouncy_bounce}");
+
+        let handle = game.collider_container[dull].collider_handle();
+        assert_eq!(game.collider_set[handle].restitution(), 0.0);
+    }
+
+    #[test]
+    fn behaviors_run_once_per_tick() {
+        // Steps its entity one unit right per tick and despawns it after three
+        #[derive(Clone)]
+        struct Walker {
+            ticks: u32,
+        }
+
+        impl EntityBehavior for Walker {
+            fn update(&mut self, ctx: &mut BehaviorCtx, entity: Entity, _delta: f32) {
+                self.ticks += 1;
+
+                let position = ctx.position(entity).unwrap();
+                ctx.set_position(entity, position + vec2(1.0, 0.0));
+
+                if self.ticks == 3 {
+                    ctx.despawn(entity);
+                }
+            }
+        }
+
+        let mut game = weightless();
+        let walker = add_ball(&mut game, vec2(0.0, 0.0));
+        game.add_script(walker, ScriptComponent::new(Walker { ticks: 0 }));
+        let disabled = add_ball(&mut game, vec2(100.0, 0.0));
+        game.add_script(disabled, ScriptComponent::new(Walker { ticks: 0 }));
+        game.set_enabled(disabled, false);
+
+        for x in [1.0, 2.0] {
+            step(&mut game, 1);
+            assert_eq!(game.position(walker), Some(vec2(x, 0.0)));
+        }
+
+        step(&mut game, 1);
+        assert!(!game.is_alive(walker));
+        assert_eq!(game.position(disabled), Some(vec2(100.0, 0.0)));
+    }
+
+    #[test]
+    fn velocity_arrows_start_at_the_interpolated_position() {
+        let mut game = weightless();
+        let ball = add_ball(&mut game, vec2(500.0, 500.0));
+        game.set_linvel(ball, vec2(120.0, 0.0));
+        step(&mut game, 1);
+        game.render_alpha = 0.5;
+
+        let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+        game.render_velocities_system(&mut renderer);
+
+        let (drawn, _rotation) = game.interpolated_position(ball, 0.5).unwrap();
+        assert_ne!(drawn, game.position(ball).unwrap());
+        match renderer.calls.first() {
+            Some(DrawCall::Line { from, .. }) => assert_eq!(*from, drawn),
+            call => panic!("expected the arrow's shaft, found {call:?}"),
+        }
+    }
+
+    #[test]
+    fn snapshots_find_entities_by_external_id_not_by_slot() {
+        let mut sender = weightless();
+        let removed = sender.new_entity("Removed");
+        sender.remove_entity(removed);
+        let sent = add_ball(&mut sender, vec2(0.0, 0.0));
+
+        // NOTE: Removes the same entity later, so its ball ends up in another slot
+        let mut receiver = weightless();
+        let removed = receiver.new_entity("Removed");
+        let received = add_ball(&mut receiver, vec2(0.0, 0.0));
+        receiver.remove_entity(removed);
+
+        assert_ne!(sent, received);
+        assert_eq!(
+            sender.external_id_of(sent),
+            receiver.external_id_of(received)
+        );
+
+        sender.set_position(sent, vec2(40.0, -20.0));
+        assert_eq!(
+            receiver.decode_positions(&sender.encode_positions()),
+            Some(1)
+        );
+        assert_eq!(receiver.position(received), Some(vec2(40.0, -20.0)));
+    }
+
+    #[test]
+    fn fixed_colliders_and_the_broadphase_outline_the_same_rect() {
+        let mut game = Game::default();
+        let ground = game.new_entity("Ground");
+        game.add_fixed_collider(
+            ground,
+            ColliderBuilder::cuboid(500.0, 10.0)
+                .translation(vector![0.0, 100.0])
+                .build(),
+        );
+
+        let outlines = |render: fn(&Game, &mut dyn Renderer)| {
+            let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+            render(&game, &mut renderer);
+
+            renderer
+                .calls
+                .iter()
+                .filter_map(|call| match call {
+                    DrawCall::RectLines { rect, .. } => Some(*rect),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+
+        let expected = vec![Rect::new(-500.0, 90.0, 1000.0, 20.0)];
+        assert_eq!(outlines(Game::render_fixed_colliders), expected);
+        assert_eq!(outlines(Game::render_broadphase_system), expected);
+    }
+}
@@ -0,0 +1,160 @@
+/*
+    Menus
+
+    NOTE: Drawn with plain macroquad shapes and text, so unlike the editor they
+          are available without the `editor` feature.
+*/
+
+use macroquad::prelude::*;
+
+const FONT_SIZE: f32 = 28.0;
+const ITEM_HEIGHT: f32 = 40.0;
+const ITEM_WIDTH: f32 = 260.0;
+
+#[derive(Debug, Clone)]
+pub struct MenuItem<T> {
+    pub label: String,
+    pub action: T,
+}
+
+/// What the menu was asked to do during a frame, read from the keyboard by the caller.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MenuInput {
+    pub up: bool,
+    pub down: bool,
+    pub confirm: bool,
+}
+
+/// A vertical list of items where `selected` wraps around at both ends.
+#[derive(Debug, Clone)]
+pub struct Menu<T> {
+    pub items: Vec<MenuItem<T>>,
+    pub selected: usize,
+}
+
+impl<T: Copy> Menu<T> {
+    pub fn new(items: impl IntoIterator<Item = (&'static str, T)>) -> Self {
+        Self {
+            items: items
+                .into_iter()
+                .map(|(label, action)| MenuItem {
+                    label: label.to_owned(),
+                    action,
+                })
+                .collect(),
+            selected: 0,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + 1) % self.items.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+        }
+    }
+
+    pub fn selected_action(&self) -> Option<T> {
+        self.items.get(self.selected).map(|item| item.action)
+    }
+
+    /// Moves the selection and returns the chosen action when `input` confirms it.
+    pub fn navigate(&mut self, input: MenuInput) -> Option<T> {
+        if input.up {
+            self.select_previous();
+        }
+
+        if input.down {
+            self.select_next();
+        }
+
+        if input.confirm {
+            self.selected_action()
+        } else {
+            None
+        }
+    }
+
+    /// Draws the items centered horizontally with the first one at `top`, in screen space.
+    pub fn render(&self, top: f32) {
+        let x = (screen_width() - ITEM_WIDTH) * 0.5;
+
+        for (i, item) in self.items.iter().enumerate() {
+            let y = top + i as f32 * ITEM_HEIGHT;
+            let selected = i == self.selected;
+
+            if selected {
+                draw_rectangle(
+                    x,
+                    y,
+                    ITEM_WIDTH,
+                    ITEM_HEIGHT,
+                    Color::new(1.0, 1.0, 1.0, 0.2),
+                );
+            }
+
+            let dimensions = measure_text(&item.label, None, FONT_SIZE as u16, 1.0);
+            draw_text(
+                &item.label,
+                (screen_width() - dimensions.width) * 0.5,
+                y + (ITEM_HEIGHT + dimensions.height) * 0.5,
+                FONT_SIZE,
+                if selected { WHITE } else { GRAY },
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UP: MenuInput = MenuInput {
+        up: true,
+        down: false,
+        confirm: false,
+    };
+    const DOWN: MenuInput = MenuInput {
+        up: false,
+        down: true,
+        confirm: false,
+    };
+    const CONFIRM: MenuInput = MenuInput {
+        up: false,
+        down: false,
+        confirm: true,
+    };
+
+    #[test]
+    fn selection_wraps_around_at_both_ends() {
+        let mut menu = Menu::new([("Play", 0), ("Settings", 1), ("Quit", 2)]);
+
+        assert_eq!(menu.navigate(UP), None);
+        assert_eq!(menu.selected, 2);
+
+        assert_eq!(menu.navigate(DOWN), None);
+        assert_eq!(menu.selected, 0);
+    }
+
+    #[test]
+    fn confirming_returns_the_selected_action() {
+        let mut menu = Menu::new([("Play", 0), ("Settings", 1), ("Quit", 2)]);
+
+        menu.navigate(DOWN);
+        assert_eq!(menu.navigate(CONFIRM), Some(1));
+        assert_eq!(menu.selected, 1, "confirming keeps the selection");
+    }
+
+    #[test]
+    fn empty_menus_select_nothing() {
+        let mut menu = Menu::<u8>::new([]);
+
+        menu.navigate(DOWN);
+        menu.navigate(UP);
+        assert_eq!(menu.navigate(CONFIRM), None);
+    }
+}
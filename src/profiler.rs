@@ -0,0 +1,120 @@
+/*
+    Profiler
+
+    NOTE: `Instant::now` panics on wasm32-unknown-unknown, so keep the profiler
+          disabled there. Rendering systems only queue draw calls, so their
+          timings are CPU time and say nothing about the GPU.
+*/
+
+use std::time::{Duration, Instant};
+
+// Weight of the newest sample in the rolling average
+const SMOOTHING: f64 = 0.05;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Timing {
+    pub name: &'static str,
+    pub last: Duration,
+    pub average: Duration,
+}
+
+#[derive(Debug, Default)]
+pub struct Timings {
+    // NOTE: In the order the systems first ran, so the overlay doesn't jump around
+    entries: Vec<Timing>,
+}
+
+impl Timings {
+    pub fn iter(&self) -> impl Iterator<Item = &Timing> {
+        self.entries.iter()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Timing> {
+        self.entries.iter().find(|timing| timing.name == name)
+    }
+
+    fn record(&mut self, name: &'static str, duration: Duration) {
+        match self.entries.iter_mut().find(|timing| timing.name == name) {
+            Some(timing) => {
+                let average = timing.average.as_secs_f64();
+                let sample = duration.as_secs_f64();
+
+                timing.last = duration;
+                timing.average = Duration::from_secs_f64(average + (sample - average) * SMOOTHING);
+            }
+
+            None => self.entries.push(Timing {
+                name,
+                last: duration,
+                average: duration,
+            }),
+        }
+    }
+}
+
+/// Times systems while `enabled`. When disabled, timing a system costs one branch.
+#[derive(Debug, Default)]
+pub struct Profiler {
+    pub enabled: bool,
+    timings: Timings,
+}
+
+impl Profiler {
+    #[inline]
+    pub fn start(&self) -> Option<Instant> {
+        self.enabled.then(Instant::now)
+    }
+
+    #[inline]
+    pub fn stop(&mut self, name: &'static str, start: Option<Instant>) {
+        if let Some(start) = start {
+            self.timings.record(name, start.elapsed());
+        }
+    }
+
+    pub fn timings(&self) -> &Timings {
+        &self.timings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sleep_timed(profiler: &mut Profiler, name: &'static str) {
+        let start = profiler.start();
+        std::thread::sleep(Duration::from_millis(1));
+        profiler.stop(name, start);
+    }
+
+    #[test]
+    fn disabled_profilers_record_nothing() {
+        let mut profiler = Profiler::default();
+        sleep_timed(&mut profiler, "sleep");
+
+        assert_eq!(profiler.timings().iter().count(), 0);
+    }
+
+    #[test]
+    fn enabled_profilers_record_each_system_once() {
+        let mut profiler = Profiler {
+            enabled: true,
+            ..Default::default()
+        };
+        for _ in 0..3 {
+            sleep_timed(&mut profiler, "sleep");
+            sleep_timed(&mut profiler, "other");
+        }
+
+        let names = profiler
+            .timings()
+            .iter()
+            .map(|timing| timing.name)
+            .collect::<Vec<_>>();
+        assert_eq!(names, ["sleep", "other"]);
+
+        let timing = profiler.timings().get("sleep").unwrap();
+        assert!(timing.last >= Duration::from_millis(1));
+        assert!(timing.average >= Duration::from_millis(1));
+    }
+}
@@ -0,0 +1,212 @@
+/*
+    Drawing
+
+    NOTE: The render systems draw through a `Renderer` instead of calling macroquad
+          themselves, so `RecordingRenderer` can check what they draw without a window.
+          Cameras still go through macroquad, so positions are the ones passed in,
+          before the camera transform.
+*/
+
+use macroquad::prelude::*;
+
+pub trait Renderer {
+    /// The size of the render target in pixels.
+    fn screen_size(&self) -> Vec2;
+
+    /// The size `text` takes up when drawn at `font_size`.
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2;
+
+    /// Draws `texture` with its top left corner at `position`.
+    fn draw_texture(
+        &mut self,
+        texture: Texture2D,
+        position: Vec2,
+        color: Color,
+        params: DrawTextureParams,
+    );
+
+    fn draw_rect(&mut self, rect: Rect, color: Color);
+    fn draw_rect_lines(&mut self, rect: Rect, thickness: f32, color: Color);
+    fn draw_line(&mut self, from: Vec2, to: Vec2, thickness: f32, color: Color);
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color);
+
+    /// Draws `text` with the baseline of its first character at `position`.
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color);
+}
+
+/// Draws to the window, or whatever render target macroquad's camera points at.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MacroquadRenderer;
+
+impl Renderer for MacroquadRenderer {
+    fn screen_size(&self) -> Vec2 {
+        vec2(screen_width(), screen_height())
+    }
+
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2 {
+        let dimensions = measure_text(text, None, font_size as u16, 1.0);
+        vec2(dimensions.width, dimensions.height)
+    }
+
+    fn draw_texture(
+        &mut self,
+        texture: Texture2D,
+        position: Vec2,
+        color: Color,
+        params: DrawTextureParams,
+    ) {
+        draw_texture_ex(texture, position.x, position.y, color, params);
+    }
+
+    fn draw_rect(&mut self, rect: Rect, color: Color) {
+        draw_rectangle(rect.x, rect.y, rect.w, rect.h, color);
+    }
+
+    fn draw_rect_lines(&mut self, rect: Rect, thickness: f32, color: Color) {
+        draw_rectangle_lines(rect.x, rect.y, rect.w, rect.h, thickness, color);
+    }
+
+    fn draw_line(&mut self, from: Vec2, to: Vec2, thickness: f32, color: Color) {
+        draw_line(from.x, from.y, to.x, to.y, thickness, color);
+    }
+
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        draw_circle(center.x, center.y, radius, color);
+    }
+
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        draw_text(text, position.x, position.y, font_size, color);
+    }
+}
+
+/// One call made to a `RecordingRenderer`, with the same arguments.
+#[derive(Debug, Clone, PartialEq)]
+pub enum DrawCall {
+    Texture {
+        texture: Texture2D,
+        position: Vec2,
+        color: Color,
+        // NOTE: The fields of `DrawTextureParams`, which can't be compared
+        dest_size: Option<Vec2>,
+        source: Option<Rect>,
+        rotation: f32,
+        flip_x: bool,
+        flip_y: bool,
+        pivot: Option<Vec2>,
+    },
+    Rect {
+        rect: Rect,
+        color: Color,
+    },
+    RectLines {
+        rect: Rect,
+        thickness: f32,
+        color: Color,
+    },
+    Line {
+        from: Vec2,
+        to: Vec2,
+        thickness: f32,
+        color: Color,
+    },
+    Circle {
+        center: Vec2,
+        radius: f32,
+        color: Color,
+    },
+    Text {
+        text: String,
+        position: Vec2,
+        font_size: f32,
+        color: Color,
+    },
+}
+
+/*
+    Records every call instead of drawing, e.g. to test the render systems headlessly.
+
+    NOTE: Without a font to measure with, every character is taken to be half as wide
+          as the font size and as tall.
+*/
+#[derive(Debug, Clone, Default)]
+pub struct RecordingRenderer {
+    pub screen_size: Vec2,
+    pub calls: Vec<DrawCall>,
+}
+
+impl RecordingRenderer {
+    pub fn new(screen_size: Vec2) -> Self {
+        Self {
+            screen_size,
+            calls: vec![],
+        }
+    }
+}
+
+impl Renderer for RecordingRenderer {
+    fn screen_size(&self) -> Vec2 {
+        self.screen_size
+    }
+
+    fn measure_text(&self, text: &str, font_size: f32) -> Vec2 {
+        vec2(text.chars().count() as f32 * font_size / 2.0, font_size)
+    }
+
+    fn draw_texture(
+        &mut self,
+        texture: Texture2D,
+        position: Vec2,
+        color: Color,
+        params: DrawTextureParams,
+    ) {
+        self.calls.push(DrawCall::Texture {
+            texture,
+            position,
+            color,
+            dest_size: params.dest_size,
+            source: params.source,
+            rotation: params.rotation,
+            flip_x: params.flip_x,
+            flip_y: params.flip_y,
+            pivot: params.pivot,
+        });
+    }
+
+    fn draw_rect(&mut self, rect: Rect, color: Color) {
+        self.calls.push(DrawCall::Rect { rect, color });
+    }
+
+    fn draw_rect_lines(&mut self, rect: Rect, thickness: f32, color: Color) {
+        self.calls.push(DrawCall::RectLines {
+            rect,
+            thickness,
+            color,
+        });
+    }
+
+    fn draw_line(&mut self, from: Vec2, to: Vec2, thickness: f32, color: Color) {
+        self.calls.push(DrawCall::Line {
+            from,
+            to,
+            thickness,
+            color,
+        });
+    }
+
+    fn draw_circle(&mut self, center: Vec2, radius: f32, color: Color) {
+        self.calls.push(DrawCall::Circle {
+            center,
+            radius,
+            color,
+        });
+    }
+
+    fn draw_text(&mut self, text: &str, position: Vec2, font_size: f32, color: Color) {
+        self.calls.push(DrawCall::Text {
+            text: text.to_owned(),
+            position,
+            font_size,
+            color,
+        });
+    }
+}
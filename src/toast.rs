@@ -0,0 +1,118 @@
+/*
+    Toasts
+
+    Short messages like "Saved!" stacked in the bottom left corner, which fade out at the
+    end of their duration. They count down in real time, so they keep going while paused.
+*/
+
+use macroquad::prelude::*;
+
+use crate::{renderer::Renderer, utils::lerp};
+
+const FONT_SIZE: f32 = 20.0;
+const MARGIN: f32 = 8.0;
+const COLOR: Color = WHITE;
+
+// Only the newest ones are drawn, the others still count down
+pub const MAX_SHOWN: usize = 5;
+// The last seconds of a toast, during which it fades from opaque to invisible
+pub const FADE_TIME: f32 = 0.5;
+
+/// Every active message with the seconds it has left, oldest first.
+#[derive(Debug, Clone, Default)]
+pub struct Toasts {
+    pub messages: Vec<(String, f32)>,
+}
+
+impl Toasts {
+    pub fn push(&mut self, text: impl Into<String>, duration: f32) {
+        self.messages.push((text.into(), duration));
+    }
+
+    /// Counts every message down by `delta` seconds and drops the expired ones.
+    pub fn update(&mut self, delta: f32) {
+        self.messages.retain_mut(|(_text, remaining)| {
+            *remaining -= delta;
+            *remaining > 0.0
+        });
+    }
+
+    /// The newest `MAX_SHOWN` messages, newest at the bottom.
+    pub fn render(&self, renderer: &mut dyn Renderer) {
+        let shown = &self.messages[self.messages.len().saturating_sub(MAX_SHOWN)..];
+        let mut y = renderer.screen_size().y - MARGIN;
+
+        for (text, remaining) in shown.iter().rev() {
+            let t = (remaining / FADE_TIME).min(1.0);
+            let faded = Color { a: 0.0, ..COLOR };
+            let color = Color::from_vec(lerp(faded.to_vec(), COLOR.to_vec(), t));
+
+            renderer.draw_text(text, vec2(MARGIN, y), FONT_SIZE, color);
+
+            y -= FONT_SIZE;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::renderer::{DrawCall, RecordingRenderer};
+
+    fn drawn(toasts: &Toasts) -> Vec<(String, f32)> {
+        let mut renderer = RecordingRenderer::new(vec2(800.0, 600.0));
+        toasts.render(&mut renderer);
+
+        renderer
+            .calls
+            .into_iter()
+            .map(|call| match call {
+                DrawCall::Text { text, color, .. } => (text, color.a),
+                call => panic!("toasts are text, found {call:?}"),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn toasts_expire_after_their_duration() {
+        let mut toasts = Toasts::default();
+        toasts.push("Saved!", 2.0);
+        toasts.push("Loaded!", 1.0);
+
+        toasts.update(0.5);
+        assert_eq!(toasts.messages.len(), 2);
+
+        toasts.update(0.75);
+        assert_eq!(drawn(&toasts), vec![("Saved!".to_owned(), 1.0)]);
+
+        // NOTE: Gone right at the end of its duration
+        toasts.update(0.75);
+        assert!(toasts.messages.is_empty());
+    }
+
+    #[test]
+    fn toasts_fade_out_at_the_end() {
+        let mut toasts = Toasts::default();
+        toasts.push("Saved!", 1.0);
+
+        toasts.update(1.0 - FADE_TIME / 2.0);
+        let [(_text, alpha)] = &drawn(&toasts)[..] else {
+            panic!("one toast is shown");
+        };
+        assert!((alpha - 0.5).abs() < 1e-4, "{alpha}");
+    }
+
+    #[test]
+    fn only_the_newest_toasts_are_shown() {
+        let mut toasts = Toasts::default();
+        for i in 0..MAX_SHOWN + 2 {
+            toasts.push(format!("{i}"), 1.0);
+        }
+
+        let shown = drawn(&toasts)
+            .into_iter()
+            .map(|(text, _alpha)| text)
+            .collect::<Vec<_>>();
+        assert_eq!(shown, ["6", "5", "4", "3", "2"]);
+    }
+}
@@ -0,0 +1,138 @@
+/*
+    Tweens
+
+    NOTE: Generic over the context the targets write into, which is `Game` in
+          practice. A tween owns its target closure, so it can't be cloned or
+          inspected beyond its progress.
+*/
+
+use crate::utils::{lerp, Easing};
+
+type Target<C> = Box<dyn FnMut(&mut C, f32)>;
+type OnComplete<C> = Box<dyn FnOnce(&mut C)>;
+
+/// Eases a float from `from` to `to` over `duration` seconds, handing every new
+/// value to `target`.
+pub struct Tween<C> {
+    target: Target<C>,
+
+    pub from: f32,
+    pub to: f32,
+    pub duration: f32,
+    pub elapsed: f32,
+    pub easing: Easing,
+
+    on_complete: Option<OnComplete<C>>,
+}
+
+impl<C> Tween<C> {
+    pub fn new(
+        from: f32,
+        to: f32,
+        duration: f32,
+        target: impl FnMut(&mut C, f32) + 'static,
+    ) -> Self {
+        Self {
+            target: Box::new(target),
+
+            from,
+            to,
+            duration,
+            elapsed: 0.0,
+            easing: Easing::Linear,
+
+            on_complete: None,
+        }
+    }
+
+    pub fn with_easing(mut self, easing: Easing) -> Self {
+        self.easing = easing;
+        self
+    }
+
+    /// Runs `on_complete` once, right after the target received `to`.
+    pub fn on_complete(mut self, on_complete: impl FnOnce(&mut C) + 'static) -> Self {
+        self.on_complete = Some(Box::new(on_complete));
+        self
+    }
+
+    /// Progress in `0.0..=1.0`, before easing.
+    pub fn progress(&self) -> f32 {
+        if self.duration > 0.0 {
+            (self.elapsed / self.duration).min(1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.progress() >= 1.0
+    }
+
+    pub fn value(&self) -> f32 {
+        lerp(self.from, self.to, self.easing.apply(self.progress()))
+    }
+
+    /// Moves the tween `delta` seconds forward and writes the new value. Returns true
+    /// once it has finished, after calling `on_complete`.
+    pub fn advance(&mut self, context: &mut C, delta: f32) -> bool {
+        self.elapsed += delta;
+
+        let value = self.value();
+        (self.target)(context, value);
+
+        if !self.is_finished() {
+            return false;
+        }
+
+        if let Some(on_complete) = self.on_complete.take() {
+            on_complete(context);
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Context {
+        value: f32,
+        completions: u32,
+    }
+
+    #[test]
+    fn tweens_end_on_their_target_value_and_complete_once() {
+        let mut context = Context::default();
+        let mut tween = Tween::new(10.0, 20.0, 1.0, |context: &mut Context, value| {
+            context.value = value;
+        })
+        .with_easing(Easing::QuadInOut)
+        .on_complete(|context| context.completions += 1);
+
+        let mut finished = vec![];
+        for _ in 0..4 {
+            finished.push(tween.advance(&mut context, 0.25));
+        }
+        assert_eq!(finished, [false, false, false, true]);
+        assert_eq!(context.value, 20.0);
+        assert_eq!(context.completions, 1);
+
+        assert!(tween.advance(&mut context, 0.25));
+        assert_eq!(context.value, 20.0, "stays at the end");
+        assert_eq!(context.completions, 1);
+    }
+
+    #[test]
+    fn zero_duration_tweens_finish_right_away() {
+        let mut context = Context::default();
+        let mut tween = Tween::new(0.0, 5.0, 0.0, |context: &mut Context, value| {
+            context.value = value;
+        });
+
+        assert!(tween.advance(&mut context, 0.0));
+        assert_eq!(context.value, 5.0);
+    }
+}
@@ -1,4 +1,7 @@
-use std::ops::{Add, Mul};
+use std::{
+    hash::Hasher,
+    ops::{Add, Mul},
+};
 
 #[inline]
 pub fn lerp<T>(a: T, b: T, t: f32) -> T
@@ -9,3 +12,184 @@ where
 {
     (1.0 - t) * a + (t * b)
 }
+
+/// 64-bit FNV-1a. Unlike `DefaultHasher` its output is the same on every
+/// platform and Rust version, which matters for checksums compared across machines.
+pub struct Fnv1aHasher(u64);
+
+impl Default for Fnv1aHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1aHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+}
+
+/// Easing curves mapping a progress `t` in `0.0..=1.0` onto `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Easing {
+    #[default]
+    Linear,
+    QuadIn,
+    QuadOut,
+    QuadInOut,
+    CubicIn,
+    CubicOut,
+    CubicInOut,
+    SineInOut,
+}
+
+impl Easing {
+    pub fn apply(self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+
+        match self {
+            Easing::Linear => t,
+
+            Easing::QuadIn => t * t,
+            Easing::QuadOut => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::QuadInOut if t < 0.5 => 2.0 * t * t,
+            Easing::QuadInOut => 1.0 - (-2.0 * t + 2.0).powi(2) / 2.0,
+
+            Easing::CubicIn => t * t * t,
+            Easing::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Easing::CubicInOut if t < 0.5 => 4.0 * t * t * t,
+            Easing::CubicInOut => 1.0 - (-2.0 * t + 2.0).powi(3) / 2.0,
+
+            Easing::SineInOut => -((std::f32::consts::PI * t).cos() - 1.0) / 2.0,
+        }
+    }
+}
+
+/// A small seeded xorshift64* generator. The same seed always yields the same
+/// sequence, so keep every random choice in the simulation on one of these.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        // NOTE: Scrambled with splitmix64 so nearby seeds don't start out correlated,
+        //       and nudged off zero, which xorshift never leaves
+        let mut z = seed.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+
+        Self {
+            state: (z ^ (z >> 31)).max(1),
+        }
+    }
+
+    /// The whole state, to restore later with `from_state`.
+    pub fn state(&self) -> u64 {
+        self.state
+    }
+
+    pub fn from_state(state: u64) -> Self {
+        Self {
+            state: state.max(1),
+        }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        self.state.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+
+    /// Uniform in `0.0..1.0`.
+    pub fn next_f32(&mut self) -> f32 {
+        // NOTE: The top 24 bits are exactly as many as an f32 mantissa holds
+        (self.next_u64() >> 40) as f32 / (1u64 << 24) as f32
+    }
+
+    /// Uniform in `low..high`, or `low` when the range is empty.
+    pub fn range(&mut self, low: f32, high: f32) -> f32 {
+        if high > low {
+            lerp(low, high, self.next_f32())
+        } else {
+            low
+        }
+    }
+}
+
+/// An exponential moving average, e.g. to keep a single long frame from showing up
+/// as a jump. Each sample moves the average `1.0 - smoothing` of the way towards it.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Smoothed {
+    // In `0.0..1.0`, where 0 passes every sample through unchanged
+    pub smoothing: f32,
+    value: Option<f32>,
+}
+
+impl Smoothed {
+    pub fn new(smoothing: f32) -> Self {
+        Self {
+            smoothing,
+            value: None,
+        }
+    }
+
+    /// The average so far, `None` before the first sample.
+    pub fn value(&self) -> Option<f32> {
+        self.value
+    }
+
+    /// Adds `sample` and returns the new average. The first sample is taken as is.
+    pub fn update(&mut self, sample: f32) -> f32 {
+        let smoothing = self.smoothing.clamp(0.0, 0.99);
+
+        let value = match self.value {
+            Some(value) => lerp(sample, value, smoothing),
+            None => sample,
+        };
+
+        self.value = Some(value);
+        value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FRAME: f32 = 1.0 / 60.0;
+
+    #[test]
+    fn a_hitch_only_moves_the_smoothed_delta_part_of_the_way() {
+        let mut delta = Smoothed::new(0.9);
+        assert_eq!(delta.value(), None);
+
+        for _ in 0..10 {
+            assert_eq!(delta.update(FRAME), FRAME);
+        }
+
+        let spike = delta.update(0.5);
+        let bound = FRAME + (0.5 - FRAME) * (1.0 - 0.9);
+        assert!(spike > FRAME && spike <= bound + 1e-6, "{spike}");
+
+        let after = (0..60).fold(spike, |_previous, _frame| delta.update(FRAME));
+        assert!((after - FRAME).abs() < 1e-4, "settles back, {after}");
+    }
+
+    #[test]
+    fn no_smoothing_passes_every_sample_through() {
+        let mut delta = Smoothed::new(0.0);
+
+        assert_eq!(delta.update(FRAME), FRAME);
+        assert_eq!(delta.update(0.5), 0.5);
+    }
+}
@@ -0,0 +1,19 @@
+/// Every key of .env and .env.local, typed by its value.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Config {
+    /// `WINDOW_TITLE`
+    pub window_title: &'static str,
+    /// `WINDOW_WIDTH`
+    pub window_width: i64,
+    /// `VSYNC`
+    pub vsync: bool,
+    /// `GRAVITY`
+    pub gravity: f64,
+}
+
+pub const CONFIG: Config = Config {
+    window_title: "rust 2d macro",
+    window_width: 1920,
+    vsync: true,
+    gravity: 9.81,
+};